@@ -1,5 +1,5 @@
 from abc import ABC, abstractmethod
-from dataclasses import dataclass
+from dataclasses import dataclass, field
 from typing import Any, Dict, Optional
 import json
 
@@ -20,6 +20,7 @@ class NodeData:
     timestamp: int
     metadata: Optional[Dict[str, Any]]
     status: str = "online"
+    labels: Dict[str, str] = field(default_factory=dict)
 
     @classmethod
     def from_json(cls, json_str: str) -> "NodeData":
@@ -41,9 +41,9 @@ class Node:
     async def initialize(self) -> None:
         logger.info(f"Node {self.id} initialized with type {self.node_type}")
         try:
-            await self.create_publisher(f"node/{self.id}/data")
+            await self.create_publisher(f"fabric/{self.id}/data")
             await self.create_subscriber(
-                f"node/{self.id}/config", self.handle_config_update
+                f"fabric/{self.id}/config", self.handle_config_update
             )
         except Exception as e:
             logger.error(f"Error initializing node {self.id}: {str(e)}", exc_info=True)
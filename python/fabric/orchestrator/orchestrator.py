@@ -114,7 +114,7 @@ class Orchestrator:
             )
 
     async def publish_node_config(self, node_id: str, config: NodeConfig) -> None:
-        topic = f"node/{node_id}/config"
+        topic = f"fabric/{node_id}/config"
         try:
             if topic not in self.publishers:
                 self.create_publisher(topic)
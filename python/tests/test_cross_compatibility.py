@@ -53,7 +53,7 @@ class MockRustNode:
 
     async def run(self, cancel_token: asyncio.Event) -> None:
         self.subscriber = self.session.declare_subscriber(
-            f"node/{self.node_id}/config", self.handle_config_update
+            f"fabric/{self.node_id}/config", self.handle_config_update
         )
         try:
             while not cancel_token.is_set():
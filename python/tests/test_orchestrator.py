@@ -35,7 +35,7 @@ async def test_publish_node_config(orchestrator):
     node_id = "test_node"
     config = NodeConfig(node_id=node_id, config={"key": "value"})
     await orchestrator.publish_node_config(node_id, config)
-    assert f"node/{node_id}/config" in orchestrator.publishers
+    assert f"fabric/{node_id}/config" in orchestrator.publishers
 
 
 @pytest.mark.asyncio
@@ -1,11 +1,11 @@
 use async_trait::async_trait;
-use fabric::node::interface::{NodeConfig, NodeData, NodeInterface};
-use fabric::node::Node;
+use fabric::node::interface::{NodeConfig, NodeInterface};
+use fabric::node::{Node, NodeOptions, StateMachine};
+use fabric::session::{open_session, SessionOptions};
+use fabric::shutdown::{self, Components};
 use fabric::Result;
 use log::{error, info, warn};
-use rand::rngs::SmallRng;
 use rand::Rng;
-use rand::SeedableRng;
 use serde::{Deserialize, Serialize};
 use std::any::Any;
 use std::env;
@@ -14,9 +14,6 @@ use tokio::sync::Mutex;
 use tokio::time::{interval, Duration};
 use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
-use zenoh::config;
-use zenoh::prelude::r#async::*;
-use zenoh::Session;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct QuadcopterConfig {
@@ -33,15 +30,23 @@ enum QuadcopterCommand {
     TakeOff,
 }
 
+fn quadcopter_modes() -> StateMachine {
+    StateMachine::new("idle")
+        .allow("idle", &["taking_off"])
+        .allow("taking_off", &["moving", "landing", "returning_home"])
+        .allow("moving", &["moving", "landing", "returning_home"])
+        .allow("returning_home", &["landing"])
+        .allow("landing", &["idle"])
+}
+
 #[derive(Clone)]
 struct QuadcopterNode {
     node_id: String,
     altitude: f32,
     battery_level: f32,
-    command_mode: String,
+    mode: StateMachine,
     config: NodeConfig,
     quadcopter_config: Arc<Mutex<QuadcopterConfig>>,
-    rng: Arc<Mutex<SmallRng>>,
 }
 
 #[async_trait]
@@ -64,22 +69,28 @@ impl NodeInterface for QuadcopterNode {
     }
 
     async fn handle_event(&mut self, event: &str, payload: &str) -> Result<()> {
-        match event {
-            "move_to" => {
-                self.command_mode = "moving".to_string();
-                info!("Moving to position: {}", payload);
+        let command = match event {
+            "move_to" => QuadcopterCommand::MoveTo(serde_json::from_str(payload)?),
+            "land" => QuadcopterCommand::Land,
+            "take_off" => QuadcopterCommand::TakeOff,
+            _ => {
+                warn!("Unknown event: {}", event);
+                return Ok(());
+            }
+        };
+        match command {
+            QuadcopterCommand::MoveTo(position) => {
+                self.mode.transition("moving")?;
+                info!("Moving to position: {:?}", position);
             }
-            "land" => {
-                self.command_mode = "landing".to_string();
+            QuadcopterCommand::Land => {
+                self.mode.transition("landing")?;
                 info!("Landing quadcopter");
             }
-            "take_off" => {
-                self.command_mode = "taking_off".to_string();
+            QuadcopterCommand::TakeOff => {
+                self.mode.transition("taking_off")?;
                 info!("Taking off");
             }
-            _ => {
-                warn!("Unknown event: {}", event);
-            }
         }
         Ok(())
     }
@@ -91,6 +102,11 @@ impl NodeInterface for QuadcopterNode {
     fn as_any(&mut self) -> &mut dyn Any {
         self
     }
+
+    // Shares the current mode with the node, so Node::mode follows handle_event
+    fn state_machine(&self) -> Option<StateMachine> {
+        Some(self.mode.clone())
+    }
 }
 
 impl QuadcopterNode {
@@ -103,31 +119,26 @@ impl QuadcopterNode {
         while !cancel_token.is_cancelled() {
             tokio::select! {
                 _ = interval.tick() => {
-                    let mut rng = self.rng.lock().await;
-                    self.altitude += rng.gen_range(-0.1..0.1);
-                    self.battery_level -= rng.gen_range(0.1..0.5);
+                    let (altitude_delta, battery_drain) = node.with_rng(|rng| {
+                        (rng.gen_range(-0.1..0.1), rng.gen_range(0.1..0.5))
+                    });
+                    self.altitude += altitude_delta;
+                    self.battery_level -= battery_drain;
 
                     let config = self.quadcopter_config.lock().await;
-                    if self.battery_level < config.battery_threshold {
+                    if self.battery_level < config.battery_threshold
+                        && self.mode.transition("returning_home").is_ok()
+                    {
                         warn!("Low battery! Returning to home position.");
-                        self.command_mode = "returning_home".to_string();
                     }
 
                     let telemetry_data = serde_json::json!({
                         "altitude": self.altitude,
                         "battery_level": self.battery_level,
-                        "command_mode": self.command_mode,
+                        "command_mode": self.mode.current(),
                     });
 
-                    let node_data = NodeData {
-                        node_id: self.node_id.clone(),
-                        node_type: self.get_type(),
-                        timestamp: chrono::Utc::now().timestamp() as u64,
-                        metadata: Some(telemetry_data),
-                        status: "online".to_string(),
-                    };
-
-                    if let Err(e) = node.publish(&telemetry_topic, serde_json::to_string(&node_data)?.into_bytes()).await {
+                    if let Err(e) = node.publish_json(&telemetry_topic, telemetry_data).await {
                         error!("Failed to publish telemetry: {:?}", e);
                     }
                 }
@@ -141,17 +152,6 @@ impl QuadcopterNode {
     }
 }
 
-async fn create_zenoh_session() -> Result<Session> {
-    let config = config::Config::default();
-    let session = zenoh::open(config)
-        .res()
-        .await
-        .map_err(fabric::error::FabricError::ZenohError)?;
-    let info = session.info();
-    info!("Zenoh session created with ZID: {:?}", info.zid());
-    Ok(session)
-}
-
 #[tokio::main]
 async fn main() -> fabric::Result<()> {
     env_logger::init();
@@ -171,7 +171,7 @@ async fn main() -> fabric::Result<()> {
     let node_name = if !node_name.starts_with("rust-quadcopter-") {
         format!(
             "rust-quadcopter-{}",
-            node_name.split('-').last().unwrap_or("")
+            node_name.split('-').next_back().unwrap_or("")
         )
     } else {
         node_name
@@ -197,7 +197,7 @@ async fn main() -> fabric::Result<()> {
         node_id: node_name.clone(),
         altitude: 0.0,
         battery_level: 100.0,
-        command_mode: "idle".to_string(),
+        mode: quadcopter_modes(),
         config: config.clone(),
         quadcopter_config: Arc::new(Mutex::new(QuadcopterConfig {
             max_altitude: 100.0,
@@ -205,34 +205,48 @@ async fn main() -> fabric::Result<()> {
             home_position: [0.0, 0.0, 0.0],
             battery_threshold: 20.0,
         })),
-        rng: Arc::new(Mutex::new(SmallRng::from_entropy())),
     };
 
-    let session = create_zenoh_session().await?;
-    let node = Node::new(
+    let session = open_session(&SessionOptions::from_env()).await?;
+    // QUADCOPTER_SEED makes the simulated telemetry reproducible
+    let options = NodeOptions {
+        seed: env::var("QUADCOPTER_SEED")
+            .ok()
+            .and_then(|seed| seed.parse().ok()),
+        ..Default::default()
+    };
+    let node = Node::with_options(
         config.node_id.clone(),
         "quadcopter".to_string(),
         config.clone(),
         Arc::new(session),
         Some(Box::new(quadcopter_node.clone())),
+        options,
     )
     .await?;
 
-    let cancel_token = CancellationToken::new();
-    let cancel_token_clone = cancel_token.clone();
+    let mut components = Components::new();
+    components.register(Arc::new(node.clone()));
+    let node_handle = node.clone().spawn(components.token());
 
     tokio::select! {
-        result = quadcopter_node.run(&node, cancel_token.clone()) => {
+        result = quadcopter_node.run(&node, components.token()) => {
             if let Err(e) = result {
                 error!("Error running quadcopter node: {:?}", e);
             }
         }
-        _ = tokio::signal::ctrl_c() => {
-            info!("Received Ctrl+C, shutting down");
-            cancel_token_clone.cancel();
+        result = shutdown::on_signal(&components, Duration::from_secs(5)) => {
+            match result {
+                Ok(failed) if !failed.is_empty() => {
+                    warn!("Components that did not stop cleanly: {:?}", failed);
+                }
+                Ok(_) => info!("Received shutdown signal, node stopped"),
+                Err(e) => error!("Error waiting for shutdown signal: {:?}", e),
+            }
         }
     }
 
+    node_handle.shutdown().await?;
     info!("Node shut down successfully");
     Ok(())
 }
@@ -1,13 +1,13 @@
-use chrono::Utc;
 use fabric::node::interface::NodeData;
 use fabric::orchestrator::Orchestrator;
+use fabric::session::{open_session, SessionOptions};
+use fabric::shutdown::{self, Components};
 use log::{error, info, warn};
 use rand::Rng;
 use serde_json::Value;
 use std::env;
 use std::time::Duration;
 use tokio::time::interval;
-use tokio_util::sync::CancellationToken;
 use zenoh::prelude::r#async::*;
 
 #[tokio::main]
@@ -17,8 +17,7 @@ async fn main() -> fabric::Result<()> {
 
     info!("Starting example orchestrator...");
 
-    let config = zenoh::config::Config::default();
-    let session = zenoh::open(config).res().await.unwrap().into_arc();
+    let session = open_session(&SessionOptions::from_env()).await?.into_arc();
 
     // Generate a random ID
     let random_id: String = rand::thread_rng()
@@ -40,8 +39,9 @@ async fn main() -> fabric::Result<()> {
 
     let orchestrator = Orchestrator::new(orchestrator_name.to_string(), session.clone()).await?;
 
-    let cancel_token = CancellationToken::new();
-    let cancel_token_clone = cancel_token.clone();
+    let mut components = Components::new();
+    components.register(orchestrator.clone());
+    let cancel_token_clone = components.token();
 
     let orchestrator_clone = orchestrator.clone();
     let orchestrator_handle = tokio::spawn(async move {
@@ -67,13 +67,12 @@ async fn main() -> fabric::Result<()> {
                     let node_type = data["node_type"].as_str().unwrap_or("unknown");
                     info!("Updating state for node: {}", node_id);
                     orchestrator_clone
-                        .update_node_state(NodeData {
-                            node_id: node_id.to_string(),
-                            node_type: node_type.to_string(),
-                            status: "online".to_string(),
-                            timestamp: Utc::now().timestamp() as u64,
-                            metadata: Some(data.clone()),
-                        })
+                        .update_node_state(
+                            NodeData::builder(node_id.to_string())
+                                .node_type(node_type.to_string())
+                                .metadata(data.clone())
+                                .build(),
+                        )
                         .await;
                 } else {
                     warn!("Received data without node_id: {:?}", data);
@@ -98,11 +97,11 @@ async fn main() -> fabric::Result<()> {
         }
     });
 
-    // Wait for a shutdown signal (e.g., Ctrl+C)
-    tokio::signal::ctrl_c().await?;
-
-    info!("Shutting down...");
-    cancel_token.cancel();
+    // Wait for Ctrl+C or SIGTERM, then stop the orchestrator within a bounded time
+    let failed = shutdown::on_signal(&components, Duration::from_secs(5)).await?;
+    if !failed.is_empty() {
+        warn!("Components that did not stop cleanly: {:?}", failed);
+    }
 
     // Wait for all tasks to finish
     let _ = tokio::join!(orchestrator_handle, subscriber_handle, log_nodes_handle);
@@ -30,6 +30,53 @@ pub enum FabricError {
 
     #[error("Invalid configuration: {0}")]
     InvalidConfig(String),
+
+    #[error("Invalid transition from {0} to {1}")]
+    InvalidTransition(String, String),
+
+    #[error("Self-test failed: {0}")]
+    SelfTestFailed(String),
+
+    #[error("Orchestrator {0} is not the leader")]
+    NotLeader(String),
+
+    #[error("Key not found in metadata: {0}")]
+    KeyNotFound(String),
+
+    #[cfg(feature = "protobuf")]
+    #[error("Protobuf decode error: {0}")]
+    ProtobufDecodeError(#[from] prost::DecodeError),
+}
+
+impl FabricError {
+    // Transient failures worth retrying; everything else fails the same way again
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            FabricError::ZenohError(_)
+            | FabricError::ZenohApiError(_)
+            | FabricError::PublishError(_) => true,
+            FabricError::IoError(e) => matches!(
+                e.kind(),
+                std::io::ErrorKind::Interrupted
+                    | std::io::ErrorKind::TimedOut
+                    | std::io::ErrorKind::WouldBlock
+                    | std::io::ErrorKind::ConnectionReset
+                    | std::io::ErrorKind::ConnectionAborted
+                    | std::io::ErrorKind::BrokenPipe
+            ),
+            FabricError::SerdeJsonError(_)
+            | FabricError::SerdeYamlError(_)
+            | FabricError::PublisherNotFound(_)
+            | FabricError::Other(_)
+            | FabricError::InvalidConfig(_)
+            | FabricError::InvalidTransition(_, _)
+            | FabricError::SelfTestFailed(_)
+            | FabricError::NotLeader(_)
+            | FabricError::KeyNotFound(_) => false,
+            #[cfg(feature = "protobuf")]
+            FabricError::ProtobufDecodeError(_) => false,
+        }
+    }
 }
 
 impl From<JoinError> for FabricError {
@@ -39,3 +86,36 @@ impl From<JoinError> for FabricError {
 }
 
 pub type Result<T> = std::result::Result<T, FabricError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_retryable_classification() {
+        let retryable = [
+            FabricError::ZenohError("session closed".into()),
+            FabricError::ZenohApiError("timeout".into()),
+            FabricError::PublishError("put failed".to_string()),
+            FabricError::IoError(std::io::ErrorKind::TimedOut.into()),
+        ];
+        for error in &retryable {
+            assert!(error.is_retryable(), "{} should be retryable", error);
+        }
+
+        let permanent = [
+            FabricError::SerdeJsonError(serde_json::from_str::<u8>("x").unwrap_err()),
+            FabricError::SerdeYamlError(serde_yaml::from_str::<u8>("[").unwrap_err()),
+            FabricError::IoError(std::io::ErrorKind::NotFound.into()),
+            FabricError::PublisherNotFound("topic".to_string()),
+            FabricError::Other("other".to_string()),
+            FabricError::InvalidConfig("bad".to_string()),
+            FabricError::InvalidTransition("idle".to_string(), "landing".to_string()),
+            FabricError::SelfTestFailed("gyro".to_string()),
+            FabricError::NotLeader("orchestrator".to_string()),
+        ];
+        for error in &permanent {
+            assert!(!error.is_retryable(), "{} should not be retryable", error);
+        }
+    }
+}
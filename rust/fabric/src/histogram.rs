@@ -0,0 +1,52 @@
+use hdrhistogram::Histogram as HdrHistogram;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HistogramSnapshot {
+    pub count: u64,
+    pub min: u64,
+    pub max: u64,
+    pub p50: u64,
+    pub p90: u64,
+    pub p99: u64,
+}
+
+#[derive(Clone)]
+pub struct Histogram {
+    inner: Arc<Mutex<HdrHistogram<u64>>>,
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Histogram {
+    pub fn new() -> Self {
+        // Three significant figures, auto-resizing as larger values arrive
+        let histogram = HdrHistogram::new(3).expect("valid histogram precision");
+        Self {
+            inner: Arc::new(Mutex::new(histogram)),
+        }
+    }
+
+    pub fn record(&self, value: u64) {
+        let _ = self.inner.lock().unwrap().record(value);
+    }
+
+    pub fn snapshot(&self) -> HistogramSnapshot {
+        let histogram = self.inner.lock().unwrap();
+        if histogram.is_empty() {
+            return HistogramSnapshot::default();
+        }
+        HistogramSnapshot {
+            count: histogram.len(),
+            min: histogram.min(),
+            max: histogram.max(),
+            p50: histogram.value_at_quantile(0.5),
+            p90: histogram.value_at_quantile(0.9),
+            p99: histogram.value_at_quantile(0.99),
+        }
+    }
+}
@@ -0,0 +1,55 @@
+// Key expressions fabric declares, all under one namespace so separate fabrics can
+// share a zenoh network without seeing each other:
+//   <namespace>/<id>/{config,config/merge,config/patch,status,state,data,audit,command,...}
+//   <namespace>/group/<group>/config
+//   <namespace>/broadcast, <namespace>/leader/<id>, <namespace>/orchestrator/<id>/...
+// "group", "broadcast", "leader" and "orchestrator" are therefore not usable as node ids.
+
+pub const DEFAULT_NAMESPACE: &str = "fabric";
+
+pub fn node_key(namespace: &str, node_id: &str, suffix: &str) -> String {
+    format!("{}/{}/{}", namespace, node_id, suffix)
+}
+
+pub fn config_key(namespace: &str, node_id: &str) -> String {
+    node_key(namespace, node_id, "config")
+}
+
+pub fn status_key(namespace: &str, node_id: &str) -> String {
+    node_key(namespace, node_id, "status")
+}
+
+pub fn state_key(namespace: &str, node_id: &str) -> String {
+    node_key(namespace, node_id, "state")
+}
+
+// The `<id>` chunk of a status key in `namespace`
+pub fn node_id_from_status_key<'a>(namespace: &str, key: &'a str) -> Option<&'a str> {
+    key.strip_prefix(namespace)?
+        .strip_prefix('/')?
+        .strip_suffix("/status")
+        .filter(|node_id| !node_id.contains('/'))
+}
+
+pub fn group_config_key(namespace: &str, group: &str) -> String {
+    format!("{}/group/{}/config", namespace, group)
+}
+
+// The `<group>` chunk of a group config key in `namespace`
+pub fn group_from_key<'a>(namespace: &str, key: &'a str) -> Option<&'a str> {
+    key.strip_prefix(namespace)?
+        .strip_prefix("/group/")?
+        .strip_suffix("/config")
+}
+
+pub fn broadcast_key(namespace: &str) -> String {
+    format!("{}/broadcast", namespace)
+}
+
+pub fn leader_key_prefix(namespace: &str) -> String {
+    format!("{}/leader", namespace)
+}
+
+pub fn orchestrator_key(namespace: &str, orchestrator_id: &str, suffix: &str) -> String {
+    format!("{}/orchestrator/{}/{}", namespace, orchestrator_id, suffix)
+}
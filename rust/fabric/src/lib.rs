@@ -1,7 +1,19 @@
 pub mod error;
+pub mod histogram;
+pub mod keys;
 pub mod logging;
 pub mod node;
 pub mod orchestrator;
+pub mod payload;
+pub mod publisher;
+#[cfg(feature = "rest")]
+pub mod rest;
+pub mod session;
+pub mod shutdown;
+pub mod subscriber;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod timestamp;
 
 pub use crate::error::FabricError;
 pub use crate::node::Node;
@@ -0,0 +1,51 @@
+use crate::node::interface::NodeConfig;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+
+pub const AUDIT_LOG_CAPACITY: usize = 100;
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AuditEntry {
+    // Milliseconds since the Unix epoch
+    pub timestamp: u64,
+    pub old_hash: String,
+    pub new_hash: String,
+    // Key expression the change arrived on, or "local" for direct API calls
+    pub source: String,
+}
+
+pub fn config_hash(config: &NodeConfig) -> String {
+    let mut hasher = DefaultHasher::new();
+    config.node_id.hash(&mut hasher);
+    config.config.to_string().hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+// Keeps the most recent entries, discarding the oldest once full
+#[derive(Debug)]
+pub struct AuditLog {
+    capacity: usize,
+    entries: VecDeque<AuditEntry>,
+}
+
+impl AuditLog {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    pub fn record(&mut self, entry: AuditEntry) {
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+
+    pub fn entries(&self) -> Vec<AuditEntry> {
+        self.entries.iter().cloned().collect()
+    }
+}
@@ -1,15 +1,40 @@
-use crate::error::Result;
+use crate::error::{FabricError, Result};
+use crate::node::state_machine::StateMachine;
+use crate::payload::PayloadEncoding;
+use crate::timestamp::{epoch_seconds, rfc3339, TimestampFormat};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::any::Any;
+use std::collections::{BTreeMap, HashMap};
+use std::time::SystemTime;
 #[async_trait]
 pub trait NodeInterface: Send + Sync {
     fn get_config(&self) -> NodeConfig;
+    // Full replacement: discard the previous config and re-initialize from this one
     async fn set_config(&mut self, config: NodeConfig);
     fn get_type(&self) -> String;
     async fn handle_event(&mut self, event: &str, payload: &str) -> Result<()>;
+    // Incremental change: receives the previous config with a patch merged in, and
+    // should reconfigure in place without a full re-initialization
     async fn update_config(&mut self, config: NodeConfig);
     fn as_any(&mut self) -> &mut dyn Any;
+    // Domain metrics merged into the node's published telemetry
+    fn metrics(&self) -> serde_json::Value {
+        serde_json::json!({})
+    }
+    // Periodic hook run every `sampling_rate` seconds; returned data is published
+    async fn tick(&mut self, _now: SystemTime) -> Result<Option<NodeData>> {
+        Ok(None)
+    }
+    // Run before the node first reports "online", e.g. hardware pings or config checks
+    async fn self_test(&self) -> Result<()> {
+        Ok(())
+    }
+    // Modes and allowed transitions driven through `Node::transition`; return a clone of
+    // the machine the interface keeps so both see the same mode
+    fn state_machine(&self) -> Option<StateMachine> {
+        None
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -18,18 +43,120 @@ pub struct NodeConfig {
     pub config: serde_json::Value,
 }
 
+impl NodeConfig {
+    // JSON merge patch (RFC 7386): objects merge recursively, nulls remove keys
+    pub fn merge(&mut self, patch: &serde_json::Value) {
+        merge_json(&mut self.config, patch);
+    }
+}
+
+// One RFC 6902 operation, e.g. `{"op": "replace", "path": "/rate", "value": 10}`
+pub use json_patch::PatchOperation as PatchOp;
+
+pub(crate) fn merge_json(target: &mut serde_json::Value, patch: &serde_json::Value) {
+    match patch {
+        serde_json::Value::Object(patch_object) => {
+            if !target.is_object() {
+                *target = serde_json::json!({});
+            }
+            let target_object = target.as_object_mut().unwrap();
+            for (key, value) in patch_object {
+                if value.is_null() {
+                    target_object.remove(key);
+                } else {
+                    merge_json(
+                        target_object
+                            .entry(key.clone())
+                            .or_insert(serde_json::Value::Null),
+                        value,
+                    );
+                }
+            }
+        }
+        _ => *target = patch.clone(),
+    }
+}
+
+// Event envelope for fabric-wide messages on `<namespace>/broadcast` and per-node commands
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BroadcastMessage {
+    pub kind: String,
+    #[serde(default)]
+    pub payload: serde_json::Value,
+}
+
+impl BroadcastMessage {
+    // String payloads are passed through as-is, anything else as JSON text
+    pub fn payload_str(&self) -> String {
+        match &self.payload {
+            serde_json::Value::String(payload) => payload.clone(),
+            serde_json::Value::Null => String::new(),
+            payload => payload.to_string(),
+        }
+    }
+}
+
+// Lifecycle state a node reports; serialized as the lowercase variant name
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeStatus {
+    #[default]
+    Online,
+    Offline,
+    // Running with a failed self-test
+    Degraded,
+    // Self-test failed and the node stopped
+    Failed,
+    // Not heard from, or reported a status this version doesn't know
+    #[serde(other)]
+    Unknown,
+}
+
+impl NodeStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NodeStatus::Online => "online",
+            NodeStatus::Offline => "offline",
+            NodeStatus::Degraded => "degraded",
+            NodeStatus::Failed => "failed",
+            NodeStatus::Unknown => "unknown",
+        }
+    }
+}
+
+impl std::fmt::Display for NodeStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for NodeStatus {
+    type Err = std::convert::Infallible;
+
+    fn from_str(status: &str) -> std::result::Result<Self, Self::Err> {
+        Ok(match status {
+            "online" => NodeStatus::Online,
+            "offline" => NodeStatus::Offline,
+            "degraded" => NodeStatus::Degraded,
+            "failed" => NodeStatus::Failed,
+            _ => NodeStatus::Unknown,
+        })
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct NodeData {
     pub node_id: String,
     pub node_type: String,
+    #[serde(deserialize_with = "crate::timestamp::epoch_or_rfc3339")]
     pub timestamp: u64,
     pub metadata: Option<serde_json::Value>,
-    #[serde(default = "default_status")]
-    pub status: String,
-}
-
-fn default_status() -> String {
-    "online".to_string()
+    #[serde(default)]
+    pub status: NodeStatus,
+    // Identifying key/value pairs the orchestrator selects nodes by
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub labels: HashMap<String, String>,
 }
 
 impl NodeData {
@@ -39,7 +166,18 @@ impl NodeData {
             node_type: "".to_string(),
             timestamp: 0,
             metadata: None,
-            status: default_status(),
+            status: NodeStatus::default(),
+            labels: HashMap::new(),
+        }
+    }
+    // Online, stamped with the current time
+    pub fn now(node_id: String, node_type: String) -> Self {
+        Self::builder(node_id).node_type(node_type).build()
+    }
+    pub fn builder(node_id: String) -> NodeDataBuilder {
+        NodeDataBuilder {
+            node_data: Self::new(node_id),
+            timestamp: None,
         }
     }
     pub fn from_json(json: &str) -> Result<Self> {
@@ -51,7 +189,7 @@ impl NodeData {
         node_type: String,
         timestamp: u64,
         metadata: Option<serde_json::Value>,
-        status: String,
+        status: NodeStatus,
     ) -> Self {
         Self {
             node_id,
@@ -59,25 +197,281 @@ impl NodeData {
             timestamp,
             metadata,
             status,
+            labels: HashMap::new(),
         }
     }
     pub fn to_json(&self) -> Result<String> {
         let json = serde_json::to_string(self)?;
         Ok(json)
     }
-    pub fn get(&self, key: &str) -> Result<String> {
-        // Find key in metadata by turning metadata into a JSON object and then getting the value
-        let metadata_json = serde_json::to_string(&self.metadata)?;
-        let metadata_obj: serde_json::Value = serde_json::from_str(&metadata_json)?;
-        let value = metadata_obj[key].to_string();
+    pub fn to_value(&self, format: TimestampFormat) -> Result<serde_json::Value> {
+        let mut value = serde_json::to_value(self)?;
+        if format == TimestampFormat::Rfc3339 {
+            value["timestamp"] =
+                rfc3339::serialize(&self.timestamp, serde_json::value::Serializer)?;
+        }
         Ok(value)
     }
-    pub fn set_status(&mut self, status: String) -> Result<()> {
+    pub fn encode(&self, encoding: PayloadEncoding, format: TimestampFormat) -> Result<Vec<u8>> {
+        encoding.encode(&self.to_value(format)?)
+    }
+    // Strings come back as-is, any other value as JSON text
+    pub fn get(&self, key: &str) -> Result<String> {
+        let value = self
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.get(key))
+            .ok_or_else(|| FabricError::KeyNotFound(key.to_string()))?;
+        Ok(match value.as_str() {
+            Some(value) => value.to_string(),
+            None => value.to_string(),
+        })
+    }
+    // Deep-merges `patch` into the metadata with the same rules as `NodeConfig::merge`
+    pub fn merge_metadata(&mut self, patch: &serde_json::Value) {
+        merge_json(self.metadata.get_or_insert(serde_json::Value::Null), patch);
+    }
+    pub fn status(&self) -> NodeStatus {
+        self.status
+    }
+    pub fn set_status(&mut self, status: NodeStatus) -> Result<()> {
         self.status = status;
         Ok(())
     }
+    // What changed going from `self` to `other`. Metadata objects are compared key by
+    // key, missing metadata counting as empty; any other metadata change is reported
+    // whole under fields["metadata"].
+    pub fn diff(&self, other: &NodeData) -> NodeDataDiff {
+        let mut diff = NodeDataDiff::default();
+        let (Ok(serde_json::Value::Object(mut old)), Ok(serde_json::Value::Object(mut new))) =
+            (serde_json::to_value(self), serde_json::to_value(other))
+        else {
+            return diff;
+        };
+        let old_metadata = old.remove("metadata").unwrap_or_default();
+        let new_metadata = new.remove("metadata").unwrap_or_default();
+        diff_maps(&old, &new, &mut diff.fields);
+        match (metadata_map(&old_metadata), metadata_map(&new_metadata)) {
+            (Some(old_map), Some(new_map)) => diff_maps(&old_map, &new_map, &mut diff.metadata),
+            _ if old_metadata != new_metadata => {
+                diff.fields.insert(
+                    "metadata".to_string(),
+                    FieldChange {
+                        old: Some(old_metadata),
+                        new: Some(new_metadata),
+                    },
+                );
+            }
+            _ => {}
+        }
+        diff
+    }
+}
+
+fn metadata_map(
+    metadata: &serde_json::Value,
+) -> Option<serde_json::Map<String, serde_json::Value>> {
+    match metadata {
+        serde_json::Value::Null => Some(serde_json::Map::new()),
+        serde_json::Value::Object(map) => Some(map.clone()),
+        _ => None,
+    }
+}
+
+fn diff_maps(
+    old: &serde_json::Map<String, serde_json::Value>,
+    new: &serde_json::Map<String, serde_json::Value>,
+    changes: &mut BTreeMap<String, FieldChange>,
+) {
+    for key in old.keys().chain(new.keys()) {
+        let (old_value, new_value) = (old.get(key), new.get(key));
+        if old_value != new_value {
+            changes.insert(
+                key.clone(),
+                FieldChange {
+                    old: old_value.cloned(),
+                    new: new_value.cloned(),
+                },
+            );
+        }
+    }
+}
+
+// A changed field or metadata key; None on the side where it was absent
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FieldChange {
+    pub old: Option<serde_json::Value>,
+    pub new: Option<serde_json::Value>,
+}
+
+// Changes between two NodeData snapshots, keyed by serialized field name and by
+// top-level metadata key
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct NodeDataDiff {
+    pub fields: BTreeMap<String, FieldChange>,
+    pub metadata: BTreeMap<String, FieldChange>,
+}
+
+impl NodeDataDiff {
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty() && self.metadata.is_empty()
+    }
+}
+
+// Minimal liveness update published instead of the full NodeData in lightweight
+// heartbeat mode
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Heartbeat {
+    pub id: String,
+    #[serde(deserialize_with = "crate::timestamp::epoch_or_rfc3339")]
+    pub ts: u64,
+    pub status: NodeStatus,
+}
+
+impl Heartbeat {
+    pub fn encode(&self, encoding: PayloadEncoding, format: TimestampFormat) -> Result<Vec<u8>> {
+        let mut value = serde_json::to_value(self)?;
+        if format == TimestampFormat::Rfc3339 {
+            value["ts"] = rfc3339::serialize(&self.ts, serde_json::value::Serializer)?;
+        }
+        encoding.encode(&value)
+    }
+    // The last full report with this heartbeat's status and time applied
+    pub fn apply_to(&self, previous: Option<&NodeData>) -> NodeData {
+        let mut node_data = previous
+            .cloned()
+            .unwrap_or_else(|| NodeData::new(self.id.clone()));
+        node_data.timestamp = self.ts;
+        node_data.status = self.status;
+        node_data
+    }
+}
+
+// Starts online with no metadata; `build` stamps the current time unless one was set
+pub struct NodeDataBuilder {
+    node_data: NodeData,
+    timestamp: Option<u64>,
+}
+
+impl NodeDataBuilder {
+    pub fn node_type(mut self, node_type: String) -> Self {
+        self.node_data.node_type = node_type;
+        self
+    }
+    pub fn metadata(mut self, metadata: serde_json::Value) -> Self {
+        self.node_data.metadata = Some(metadata);
+        self
+    }
+    pub fn status(mut self, status: NodeStatus) -> Self {
+        self.node_data.status = status;
+        self
+    }
+    pub fn labels(mut self, labels: HashMap<String, String>) -> Self {
+        self.node_data.labels = labels;
+        self
+    }
+    pub fn timestamp(mut self, timestamp: u64) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+    pub fn build(self) -> NodeData {
+        NodeData {
+            timestamp: self.timestamp.unwrap_or_else(epoch_seconds),
+            ..self.node_data
+        }
+    }
 }
 
 pub trait NodeFactory: Send + Sync {
     fn create(&self, config: NodeConfig) -> Box<dyn NodeInterface>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_node_data_builder_defaults() {
+        let before = epoch_seconds();
+        let node_data = NodeData::builder("builder_node".to_string()).build();
+        assert_eq!(node_data.node_id, "builder_node");
+        assert_eq!(node_data.node_type, "");
+        assert_eq!(node_data.status, NodeStatus::Online);
+        assert_eq!(node_data.metadata, None);
+        assert!(node_data.labels.is_empty());
+        assert!(node_data.timestamp >= before && node_data.timestamp <= epoch_seconds());
+
+        let node_data = NodeData::builder("builder_node".to_string())
+            .node_type("rover".to_string())
+            .metadata(serde_json::json!({"speed": 2}))
+            .status(NodeStatus::Degraded)
+            .timestamp(42)
+            .build();
+        assert_eq!(node_data.node_type, "rover");
+        assert_eq!(node_data.metadata, Some(serde_json::json!({"speed": 2})));
+        assert_eq!(node_data.status, NodeStatus::Degraded);
+        assert_eq!(node_data.timestamp, 42);
+
+        let node_data = NodeData::now("now_node".to_string(), "quadcopter".to_string());
+        assert_eq!(node_data.node_type, "quadcopter");
+        assert_eq!(node_data.status, NodeStatus::Online);
+        assert!(node_data.timestamp >= before);
+    }
+
+    #[test]
+    fn test_node_data_get() {
+        let node_data = NodeData::builder("get_node".to_string())
+            .metadata(serde_json::json!({"mode": "hover", "altitude": 12.5}))
+            .build();
+        assert_eq!(node_data.get("mode").unwrap(), "hover");
+        assert_eq!(node_data.get("altitude").unwrap(), "12.5");
+        assert!(matches!(
+            node_data.get("battery"),
+            Err(FabricError::KeyNotFound(key)) if key == "battery"
+        ));
+        assert!(matches!(
+            NodeData::new("empty_node".to_string()).get("mode"),
+            Err(FabricError::KeyNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_node_data_diff() {
+        let before = NodeData::builder("diff_node".to_string())
+            .metadata(serde_json::json!({"altitude": 10, "mode": "hover"}))
+            .timestamp(100)
+            .build();
+        let after = NodeData::builder("diff_node".to_string())
+            .metadata(serde_json::json!({"altitude": 12, "mode": "hover"}))
+            .status(NodeStatus::Degraded)
+            .timestamp(100)
+            .build();
+
+        let diff = before.diff(&after);
+        assert_eq!(diff.fields.len(), 1);
+        assert_eq!(
+            diff.fields["status"],
+            FieldChange {
+                old: Some(serde_json::json!("online")),
+                new: Some(serde_json::json!("degraded")),
+            }
+        );
+        assert_eq!(diff.metadata.len(), 1);
+        assert_eq!(
+            diff.metadata["altitude"],
+            FieldChange {
+                old: Some(serde_json::json!(10)),
+                new: Some(serde_json::json!(12)),
+            }
+        );
+        assert!(before.diff(&before).is_empty());
+
+        // Keys appearing in metadata that was previously missing
+        let diff = NodeData::new("diff_node".to_string()).diff(&NodeData {
+            metadata: Some(serde_json::json!({"mode": "land"})),
+            ..NodeData::new("diff_node".to_string())
+        });
+        assert!(diff.fields.is_empty());
+        assert_eq!(diff.metadata["mode"].old, None);
+    }
+}
@@ -1,10 +1,16 @@
 #[allow(clippy::module_inception)]
 mod node;
 
+pub mod audit;
 pub mod generic;
 pub mod interface;
+pub mod persistence;
+#[cfg(feature = "protobuf")]
+pub mod protobuf;
+pub mod state_machine;
 
-pub use node::Node;
+pub use node::{Node, NodeHandle, NodeOptions, SelfTestPolicy};
+pub use state_machine::StateMachine;
 
 impl Node {
     // ... (other methods)
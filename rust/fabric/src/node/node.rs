@@ -1,16 +1,96 @@
 use crate::error::{FabricError, Result};
+use crate::histogram::{Histogram, HistogramSnapshot};
+use crate::keys;
+use crate::node::audit::{config_hash, AuditEntry, AuditLog, AUDIT_LOG_CAPACITY};
 use crate::node::generic::GenericNode;
-use crate::node::interface::NodeData;
-use crate::node::interface::{NodeConfig, NodeInterface};
+use crate::node::interface::{BroadcastMessage, NodeConfig, NodeInterface};
+use crate::node::interface::{Heartbeat, NodeData, NodeStatus, PatchOp};
+use crate::node::persistence;
+use crate::node::state_machine::StateMachine;
+use crate::orchestrator::RetryConfig;
+use crate::payload::{decode_json, decode_value, negotiated_key, round_floats, PayloadEncoding};
+use crate::publisher::PublisherOptions;
+use crate::shutdown::Component;
+use crate::subscriber::{
+    exceeds_max_payload, Deduplicator, SubscriberOptions, ZenohSubscriber,
+    DEFAULT_MAX_PAYLOAD_BYTES,
+};
+use crate::timestamp::TimestampFormat;
+use async_trait::async_trait;
+use backoff::backoff::Backoff;
+use backoff::ExponentialBackoff;
+use futures::Stream;
 use log::{debug, error, info, warn};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::de::DeserializeOwned;
 use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use tokio::sync::mpsc;
+use tokio::sync::{broadcast, mpsc};
 use tokio::sync::{Mutex, RwLock};
 use tokio::time::{interval, Duration};
 use tokio_util::sync::CancellationToken;
 use zenoh::prelude::r#async::*;
 
+const CONFIG_CHANGES_CAPACITY: usize = 16;
+const NODE_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+const DEFAULT_MAX_SUBSCRIBER_FAILURES: u32 = 10;
+const DEFAULT_INGEST_CAPACITY: usize = 100;
+
+fn current_timestamp() -> Result<u64> {
+    Ok(std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_err(|e| FabricError::Other(e.to_string()))?
+        .as_secs())
+}
+
+// The last tick slot at or before `now`, in whole periods after `deadline`; ticks missed
+// while the node was busy are skipped rather than fired back to back
+fn skip_missed_ticks(
+    deadline: tokio::time::Instant,
+    period: Duration,
+    now: tokio::time::Instant,
+) -> tokio::time::Instant {
+    let period_nanos = period.as_nanos().max(1);
+    let behind = now.saturating_duration_since(deadline).as_nanos();
+    deadline + Duration::from_nanos((behind / period_nanos * period_nanos) as u64)
+}
+
+// Consecutive receive failures of a subscriber and the backoff between re-declares
+struct SubscriberRetry {
+    backoff: ExponentialBackoff,
+    max_interval: Duration,
+    failures: u32,
+    max_failures: u32,
+}
+
+impl SubscriberRetry {
+    fn new(retry: &RetryConfig, max_failures: u32) -> Self {
+        Self {
+            backoff: retry.backoff(),
+            max_interval: retry.max_interval,
+            failures: 0,
+            max_failures,
+        }
+    }
+
+    fn succeeded(&mut self) {
+        self.failures = 0;
+        self.backoff.reset();
+    }
+
+    // How long to wait before re-declaring, or None once max_failures are reached
+    fn failed(&mut self) -> Option<Duration> {
+        self.failures += 1;
+        if self.failures >= self.max_failures {
+            return None;
+        }
+        Some(self.backoff.next_backoff().unwrap_or(self.max_interval))
+    }
+}
+
 struct Publisher {
     topic: String,
     zenoh_publisher: zenoh::publication::Publisher<'static>,
@@ -18,8 +98,109 @@ struct Publisher {
 
 pub struct Subscriber {
     topic: String,
-    callback: Arc<Mutex<dyn Fn(Sample) + Send + Sync>>,
-    zenoh_subscriber: zenoh::subscriber::Subscriber<'static, ()>,
+    // Feeds this subscriber's dispatch task, which runs its callback in order
+    dispatch_tx: mpsc::Sender<Sample>,
+    zenoh_subscriber: ZenohSubscriber,
+    dedup: Option<std::sync::Mutex<Deduplicator>>,
+}
+
+impl Subscriber {
+    fn accepts(&self, sample: &Sample) -> bool {
+        if !self
+            .zenoh_subscriber
+            .key_expr()
+            .intersects(sample.key_expr.as_keyexpr())
+        {
+            return false;
+        }
+        if let Some(dedup) = &self.dedup {
+            if !dedup.lock().unwrap().observe(sample) {
+                debug!("Dropping duplicate sample on {}", self.topic);
+                return false;
+            }
+        }
+        true
+    }
+}
+
+struct RetainedTopic {
+    value: Arc<std::sync::RwLock<Vec<u8>>>,
+    _queryable: zenoh::queryable::Queryable<'static, ()>,
+}
+
+#[derive(Clone, Debug)]
+pub struct NodeOptions {
+    // Pull the config served by the orchestrator before announcing "online"
+    pub fetch_config_timeout: Option<Duration>,
+    // Seed the node's RNG so simulations replay identically; entropy when unset
+    pub seed: Option<u64>,
+    pub self_test_policy: SelfTestPolicy,
+    // Also publish each config audit entry to <namespace>/<id>/audit
+    pub publish_audit: bool,
+    // Round floats in published telemetry to this many significant digits
+    pub float_significant_digits: Option<u32>,
+    // Announced with every status update so orchestrators can select on them
+    pub labels: HashMap<String, String>,
+    // Wire form of timestamps in published status and telemetry
+    pub timestamp_format: TimestampFormat,
+    // Encoding of published status and served configs; incoming configs are decoded
+    // by their declared encoding whatever this is set to
+    pub encoding: PayloadEncoding,
+    // Periodic heartbeats carry only id, time and status; the full NodeData goes out
+    // on <namespace>/<id>/data at the sampling rate instead
+    pub lightweight_heartbeat: bool,
+    // Heartbeats become liveness pings, with the full status sent only when it
+    // changes or this long after it was last sent
+    pub status_keepalive: Option<Duration>,
+    // Backoff between re-declaring the config subscriber after it errors
+    pub subscriber_retry: RetryConfig,
+    // Consecutive config subscriber failures before run gives up; 10 when unset
+    pub max_subscriber_failures: Option<u32>,
+    // Save every applied config here and start from it on restart, falling back to
+    // the passed config when the file is missing or unreadable
+    pub config_path: Option<PathBuf>,
+    // First chunk of every key expression the node uses, isolating it from other
+    // fabrics; "fabric" by default
+    pub namespace: String,
+    // Configs, group configs, broadcasts and commands larger than this are dropped
+    // unread; 8 MiB when unset
+    pub max_payload_bytes: Option<usize>,
+    // Subscriber samples arriving while this many are already queued, for routing or for
+    // a subscriber's callback, are dropped and counted; 100 when unset
+    pub ingest_capacity: Option<usize>,
+}
+
+impl Default for NodeOptions {
+    fn default() -> Self {
+        Self {
+            fetch_config_timeout: None,
+            seed: None,
+            self_test_policy: SelfTestPolicy::default(),
+            publish_audit: false,
+            float_significant_digits: None,
+            labels: HashMap::new(),
+            timestamp_format: TimestampFormat::default(),
+            encoding: PayloadEncoding::default(),
+            lightweight_heartbeat: false,
+            status_keepalive: None,
+            subscriber_retry: RetryConfig::default(),
+            max_subscriber_failures: None,
+            config_path: None,
+            namespace: keys::DEFAULT_NAMESPACE.to_string(),
+            max_payload_bytes: None,
+            ingest_capacity: None,
+        }
+    }
+}
+
+// What a node does when its interface self-test fails at startup
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SelfTestPolicy {
+    // Report "failed" and stop without entering the main loop
+    #[default]
+    FailFast,
+    // Keep running, reporting "degraded" instead of "online"
+    Degraded,
 }
 
 #[derive(Clone)]
@@ -31,7 +212,19 @@ pub struct Node {
     interface: Arc<Mutex<Box<dyn NodeInterface + Send + Sync>>>,
     publishers: Arc<RwLock<HashMap<String, Publisher>>>,
     subscribers: Arc<RwLock<HashMap<String, Subscriber>>>,
+    retained: Arc<RwLock<HashMap<String, RetainedTopic>>>,
     subscriber_tx: mpsc::Sender<Sample>,
+    options: NodeOptions,
+    payload_sizes: Histogram,
+    config_latency: Histogram,
+    oversized_dropped: Arc<AtomicU64>,
+    ingest_dropped: Arc<AtomicU64>,
+    malformed_dropped: Arc<AtomicU64>,
+    state_machine: Arc<std::sync::Mutex<Option<StateMachine>>>,
+    rng: Arc<std::sync::Mutex<StdRng>>,
+    self_test_error: Arc<std::sync::RwLock<Option<String>>>,
+    audit: Arc<std::sync::Mutex<AuditLog>>,
+    config_changes: broadcast::Sender<NodeConfig>,
 }
 
 impl Node {
@@ -42,11 +235,45 @@ impl Node {
         session: Arc<Session>,
         interface: Option<Box<dyn NodeInterface + Send + Sync>>,
     ) -> Result<Self> {
-        let (subscriber_tx, subscriber_rx) = mpsc::channel(100);
+        Self::with_options(
+            id,
+            node_type,
+            config,
+            session,
+            interface,
+            NodeOptions::default(),
+        )
+        .await
+    }
+
+    pub async fn with_options(
+        id: String,
+        node_type: String,
+        config: NodeConfig,
+        session: Arc<Session>,
+        interface: Option<Box<dyn NodeInterface + Send + Sync>>,
+        options: NodeOptions,
+    ) -> Result<Self> {
+        let (subscriber_tx, subscriber_rx) =
+            mpsc::channel(options.ingest_capacity.unwrap_or(DEFAULT_INGEST_CAPACITY));
+        let config = match &options.config_path {
+            Some(path) => persistence::newest(config, persistence::load(path).await),
+            None => config,
+        };
         let interface = match interface {
-            Some(interface) => interface,
+            Some(mut interface) => {
+                if options.config_path.is_some() {
+                    interface.set_config(config.clone()).await;
+                }
+                interface
+            }
             None => Box::new(GenericNode::new(config.clone())),
         };
+        let state_machine = interface.state_machine();
+        let rng = match options.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
 
         let node = Node {
             id,
@@ -56,7 +283,19 @@ impl Node {
             interface: Arc::new(Mutex::new(interface)),
             publishers: Arc::new(RwLock::new(HashMap::new())),
             subscribers: Arc::new(RwLock::new(HashMap::new())),
+            retained: Arc::new(RwLock::new(HashMap::new())),
             subscriber_tx,
+            options,
+            payload_sizes: Histogram::new(),
+            config_latency: Histogram::new(),
+            oversized_dropped: Arc::new(AtomicU64::new(0)),
+            ingest_dropped: Arc::new(AtomicU64::new(0)),
+            malformed_dropped: Arc::new(AtomicU64::new(0)),
+            state_machine: Arc::new(std::sync::Mutex::new(state_machine)),
+            rng: Arc::new(std::sync::Mutex::new(rng)),
+            self_test_error: Arc::new(std::sync::RwLock::new(None)),
+            audit: Arc::new(std::sync::Mutex::new(AuditLog::new(AUDIT_LOG_CAPACITY))),
+            config_changes: broadcast::channel(CONFIG_CHANGES_CAPACITY).0,
         };
 
         // Spawn a task to handle subscriber samples
@@ -69,33 +308,137 @@ impl Node {
     }
 
     pub async fn run(&self, cancel: CancellationToken) -> Result<()> {
-        info!("Starting node {}", self.id);
+        info!("Starting node {} on session {}", self.id, self.zid());
 
-        let key_expr = format!("node/{}/config", self.id);
-        let config_subscriber = self
+        let key_expr = self.key("config");
+        let mut config_subscriber = self
             .session
             .declare_subscriber(&key_expr)
             .res()
             .await
             .map_err(FabricError::ZenohError)?;
 
+        let merge_key_expr = self.key("config/merge");
+        let merge_subscriber = self
+            .session
+            .declare_subscriber(&merge_key_expr)
+            .res()
+            .await
+            .map_err(FabricError::ZenohError)?;
+
+        let patch_key_expr = self.key("config/patch");
+        let patch_subscriber = self
+            .session
+            .declare_subscriber(&patch_key_expr)
+            .res()
+            .await
+            .map_err(FabricError::ZenohError)?;
+
+        // Serve the applied config so orchestrators can verify it
+        let active_config_queryable = {
+            let self_clone = self.clone();
+            self.session
+                .declare_queryable(self.key("config/active"))
+                .callback(move |query| {
+                    let self_clone = self_clone.clone();
+                    tokio::spawn(async move {
+                        self_clone.reply_active_config(query).await;
+                    });
+                })
+                .res()
+                .await
+                .map_err(FabricError::ZenohError)?
+        };
+
+        let state_queryable = self.declare_state_queryable().await?;
+
+        // Group configs are merge patches addressed to every member of a group
+        let group_subscriber = self
+            .session
+            .declare_subscriber(keys::group_config_key(self.namespace(), "*"))
+            .res()
+            .await
+            .map_err(FabricError::ZenohError)?;
+
+        let broadcast_subscriber = self
+            .session
+            .declare_subscriber(keys::broadcast_key(self.namespace()))
+            .res()
+            .await
+            .map_err(FabricError::ZenohError)?;
+
+        let command_subscriber = self
+            .session
+            .declare_subscriber(self.key("command"))
+            .res()
+            .await
+            .map_err(FabricError::ZenohError)?;
+
+        if let Some(timeout) = self.options.fetch_config_timeout {
+            match self.fetch_config(timeout).await {
+                Ok(Some(_)) => info!("Node {} applied fetched configuration", self.id),
+                Ok(None) => debug!("No configuration available for node {}", self.id),
+                Err(e) => warn!(
+                    "Failed to fetch configuration for node {}: {:?}",
+                    self.id, e
+                ),
+            }
+        }
+
+        self.run_self_test().await?;
+
         // Initial status update
-        self.update_status("online".to_string()).await?;
+        self.update_status(self.heartbeat_status()).await?;
+
+        // The loop can also stop on its own when the config subscriber keeps failing,
+        // so the background tasks get a token that run cancels on the way out
+        let tasks_cancel = cancel.child_token();
 
         // Spawn a task for periodic status updates
         let status_update_task = {
-            let cancel_clone = cancel.clone();
+            let cancel_clone = tasks_cancel.clone();
             let self_clone = self.clone();
             tokio::spawn(async move {
                 let mut interval = interval(Duration::from_millis(1000));
+                let mut last_full = (self_clone.heartbeat_status(), tokio::time::Instant::now());
+                let mut failures = 0u32;
                 loop {
                     tokio::select! {
                         _ = cancel_clone.cancelled() => {
                             break;
                         }
                         _ = interval.tick() => {
-                            if let Err(e) = self_clone.update_status("online".to_string()).await {
-                                warn!("Failed to update status for node {}: {:?}", self_clone.id, e);
+                            let status = self_clone.heartbeat_status();
+                            let full = match self_clone.options.status_keepalive {
+                                Some(keepalive) => {
+                                    status != last_full.0 || last_full.1.elapsed() >= keepalive
+                                }
+                                None => !self_clone.options.lightweight_heartbeat,
+                            };
+                            let result = if full {
+                                last_full = (status, tokio::time::Instant::now());
+                                self_clone.update_status(status).await
+                            } else {
+                                self_clone.publish_heartbeat(status).await
+                            };
+                            // Keep ticking through an outage, but only warn when it starts
+                            match result {
+                                Ok(()) if failures > 0 => {
+                                    info!(
+                                        "Status updates for node {} resumed after {} failures",
+                                        self_clone.id, failures
+                                    );
+                                    failures = 0;
+                                }
+                                Ok(()) => {}
+                                Err(e) if failures == 0 => {
+                                    failures = 1;
+                                    warn!("Failed to update status for node {}: {:?}", self_clone.id, e);
+                                }
+                                Err(e) => {
+                                    failures += 1;
+                                    debug!("Failed to update status for node {}: {:?}", self_clone.id, e);
+                                }
                             }
                         }
                     }
@@ -103,7 +446,57 @@ impl Node {
             })
         };
 
+        // Spawn a task driving the interface's periodic tick
+        let tick_task = {
+            let cancel_clone = tasks_cancel.clone();
+            let self_clone = self.clone();
+            tokio::spawn(async move {
+                // Re-read sampling_rate on every config change so a shorter interval
+                // moves the pending deadline instead of waiting out the old one
+                let mut config_changes = self_clone.config_changes.subscribe();
+                let mut period = self_clone.sampling_interval().await;
+                // Deadlines advance from the previous deadline rather than from when the
+                // tick ran, so wakeup and processing time don't make the schedule drift
+                let mut last_deadline: Option<tokio::time::Instant> = None;
+                loop {
+                    let deadline = period.map(|period| {
+                        last_deadline.map_or_else(tokio::time::Instant::now, |last| last + period)
+                    });
+                    let next_tick = tokio::time::sleep_until(
+                        deadline.unwrap_or_else(tokio::time::Instant::now),
+                    );
+                    tokio::select! {
+                        _ = cancel_clone.cancelled() => {
+                            break;
+                        }
+                        _ = config_changes.recv() => {
+                            period = self_clone.sampling_interval().await;
+                        }
+                        _ = next_tick, if deadline.is_some() => {
+                            if let Err(e) = self_clone.tick().await {
+                                warn!("Tick failed for node {}: {:?}", self_clone.id, e);
+                            }
+                            last_deadline = deadline.zip(period).map(|(deadline, period)| {
+                                skip_missed_ticks(deadline, period, tokio::time::Instant::now())
+                            });
+                        }
+                    }
+                }
+            })
+        };
+
+        let mut config_retry = SubscriberRetry::new(
+            &self.options.subscriber_retry,
+            self.options
+                .max_subscriber_failures
+                .unwrap_or(DEFAULT_MAX_SUBSCRIBER_FAILURES),
+        );
+        let mut failure = None;
+
         loop {
+            // flume receivers don't take part in tokio's coop budget, so during a burst of
+            // config messages every branch stays ready; yield so heartbeats still run
+            tokio::task::yield_now().await;
             tokio::select! {
                 _ = cancel.cancelled() => {
                     info!("Node {} received cancellation signal", self.id);
@@ -111,38 +504,543 @@ impl Node {
                 }
                 sample = config_subscriber.recv_async() => {
                     match sample {
+                        Ok(sample) if self.oversized(&sample) => {}
                         Ok(sample) => {
-                            let new_config: NodeConfig = serde_json::from_slice(sample.value.payload.contiguous().as_ref())
-                                .map_err(FabricError::SerdeJsonError)?;
+                            config_retry.succeeded();
+                            let Some(new_config) = self.decode_sample::<NodeConfig>(&sample) else {
+                                continue;
+                            };
                             info!("Node {} received new configuration: {:?}", self.id, new_config);
-                            self.update_config(new_config).await?;
+                            if let Err(e) = self.set_config_from(new_config, sample.key_expr.as_str()).await {
+                                warn!("Node {} failed to apply configuration: {:?}", self.id, e);
+                            }
+                        }
+                        Err(e) => {
+                            // A disconnected receiver errors immediately, so back off and
+                            // re-declare rather than spinning on it
+                            let delay = config_retry.failed();
+                            warn!(
+                                "Error receiving configuration for node {} ({}/{}): {:?}",
+                                self.id, config_retry.failures, config_retry.max_failures, e
+                            );
+                            let Some(delay) = delay else {
+                                failure = Some(FabricError::Other(format!(
+                                    "Config subscriber for node {} failed {} times in a row",
+                                    self.id, config_retry.failures
+                                )));
+                                break;
+                            };
+                            tokio::select! {
+                                _ = cancel.cancelled() => break,
+                                _ = tokio::time::sleep(delay) => {}
+                            }
+                            match self.session.declare_subscriber(&key_expr).res().await {
+                                Ok(subscriber) => config_subscriber = subscriber,
+                                Err(e) => warn!(
+                                    "Failed to re-declare config subscriber for node {}: {:?}",
+                                    self.id, e
+                                ),
+                            }
+                        }
+                    }
+                }
+                sample = merge_subscriber.recv_async() => {
+                    match sample {
+                        Ok(sample) if self.oversized(&sample) => {}
+                        Ok(sample) => {
+                            let Some(patch) = self.decode_sample::<serde_json::Value>(&sample) else {
+                                continue;
+                            };
+                            info!("Node {} received configuration update: {:?}", self.id, patch);
+                            if let Err(e) = self.update_config_from(NodeConfig {
+                                node_id: self.id.clone(),
+                                config: patch,
+                            }, sample.key_expr.as_str())
+                            .await
+                            {
+                                warn!("Node {} failed to apply configuration update: {:?}", self.id, e);
+                            }
+                        }
+                        Err(e) => {
+                            warn!("Error receiving configuration update for node {}: {:?}", self.id, e);
+                        }
+                    }
+                }
+                sample = patch_subscriber.recv_async() => {
+                    match sample {
+                        Ok(sample) if self.oversized(&sample) => {}
+                        Ok(sample) => {
+                            let Some(ops) = self.decode_sample::<Vec<PatchOp>>(&sample) else {
+                                continue;
+                            };
+                            info!("Node {} received configuration patch: {:?}", self.id, ops);
+                            if let Err(e) = self.patch_config_from(&ops, sample.key_expr.as_str()).await {
+                                warn!("Node {} rejected configuration patch: {:?}", self.id, e);
+                            }
+                        }
+                        Err(e) => {
+                            warn!("Error receiving configuration patch for node {}: {:?}", self.id, e);
+                        }
+                    }
+                }
+                sample = group_subscriber.recv_async() => {
+                    match sample {
+                        Ok(sample) if self.oversized(&sample) => {}
+                        Ok(sample) => {
+                            let group = keys::group_from_key(self.namespace(), sample.key_expr.as_str()).unwrap_or_default().to_string();
+                            if !self.get_groups().await.contains(&group) {
+                                continue;
+                            }
+                            let Some(patch) = self.decode_sample::<serde_json::Value>(&sample) else {
+                                continue;
+                            };
+                            info!("Node {} received configuration for group {}: {:?}", self.id, group, patch);
+                            if let Err(e) = self.update_config_from(NodeConfig {
+                                node_id: self.id.clone(),
+                                config: patch,
+                            }, sample.key_expr.as_str())
+                            .await
+                            {
+                                warn!("Node {} failed to apply group configuration: {:?}", self.id, e);
+                            }
+                        }
+                        Err(e) => {
+                            warn!("Error receiving group configuration for node {}: {:?}", self.id, e);
+                        }
+                    }
+                }
+                sample = broadcast_subscriber.recv_async() => {
+                    match sample {
+                        Ok(sample) if self.oversized(&sample) => {}
+                        Ok(sample) => match decode_json::<BroadcastMessage>(&sample.value.payload) {
+                            Ok(message) => self.dispatch_event(message).await,
+                            Err(e) => self.count_malformed(&sample, &e),
+                        },
+                        Err(e) => {
+                            warn!("Error receiving broadcast for node {}: {:?}", self.id, e);
                         }
+                    }
+                }
+                sample = command_subscriber.recv_async() => {
+                    match sample {
+                        Ok(sample) if self.oversized(&sample) => {}
+                        Ok(sample) => match decode_json::<BroadcastMessage>(&sample.value.payload) {
+                            Ok(message) => self.dispatch_event(message).await,
+                            Err(e) => self.count_malformed(&sample, &e),
+                        },
                         Err(e) => {
-                            warn!("Error receiving configuration for node {}: {:?}", self.id, e);
+                            warn!("Error receiving command for node {}: {:?}", self.id, e);
                         }
                     }
                 }
             }
         }
 
-        // Wait for the status update task to complete
+        // Stop the status update and tick tasks before anything else can fail, so a
+        // broken session never leaves them publishing
+        tasks_cancel.cancel();
         status_update_task
             .await
             .map_err(|e| FabricError::Other(format!("Status update task error: {}", e)))?;
+        tick_task
+            .await
+            .map_err(|e| FabricError::Other(format!("Tick task error: {}", e)))?;
+
+        // The session may be what stopped the loop, so failing to undeclare is only
+        // logged; it must not hide `failure` or skip the offline report
+        if let Err(e) = active_config_queryable.undeclare().res().await {
+            warn!(
+                "Failed to undeclare active config queryable for node {}: {:?}",
+                self.id, e
+            );
+        }
+        if let Err(e) = state_queryable.undeclare().res().await {
+            warn!(
+                "Failed to undeclare state queryable for node {}: {:?}",
+                self.id, e
+            );
+        }
+
+        // Tell orchestrators this was a deliberate stop rather than waiting for the
+        // heartbeat timeout; the session may already be going away, so only warn
+        if let Err(e) = self.update_status(NodeStatus::Offline).await {
+            warn!("Failed to report node {} offline: {:?}", self.id, e);
+        }
 
+        if let Some(e) = failure {
+            error!("Node {} stopped: {:?}", self.id, e);
+            return Err(e);
+        }
         info!("Node {} stopped", self.id);
         Ok(())
     }
 
-    pub async fn update_config(&self, new_config: NodeConfig) -> Result<()> {
-        self.interface
+    // Run the node on its own task; the handle cancels and joins it
+    pub fn spawn(self, cancel: CancellationToken) -> NodeHandle {
+        let node_id = self.id.clone();
+        let run_cancel = cancel.clone();
+        let task = tokio::spawn(async move { self.run(run_cancel).await });
+        NodeHandle {
+            node_id,
+            cancel,
+            task,
+        }
+    }
+
+    // Configs stamped with an epoch-millis "timestamp" key feed the apply latency histogram
+    fn record_config_latency(&self, config: &NodeConfig) {
+        let Some(sent_at) = config.config["timestamp"].as_u64() else {
+            return;
+        };
+        if let Ok(now) = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH) {
+            self.config_latency
+                .record((now.as_millis() as u64).saturating_sub(sent_at));
+        }
+    }
+
+    pub fn oversized_dropped(&self) -> u64 {
+        self.oversized_dropped.load(Ordering::Relaxed)
+    }
+
+    pub fn ingest_dropped(&self) -> u64 {
+        self.ingest_dropped.load(Ordering::Relaxed)
+    }
+
+    pub fn malformed_dropped(&self) -> u64 {
+        self.malformed_dropped.load(Ordering::Relaxed)
+    }
+
+    // Payloads arrive from the network, so one that fails to decode is logged, counted
+    // and skipped rather than stopping the node
+    fn decode_sample<T: DeserializeOwned>(&self, sample: &Sample) -> Option<T> {
+        decode_value(&sample.value)
+            .map_err(|e| self.count_malformed(sample, &e))
+            .ok()
+    }
+
+    fn count_malformed(&self, sample: &Sample, error: &FabricError) {
+        let dropped = self.malformed_dropped.fetch_add(1, Ordering::Relaxed) + 1;
+        warn!(
+            "Node {} dropped malformed sample on {} ({} dropped so far): {:?}",
+            self.id, sample.key_expr, dropped, error
+        );
+    }
+
+    // Count and skip samples over the node's payload limit before they are decoded
+    fn oversized(&self, sample: &Sample) -> bool {
+        let max_payload_bytes = self
+            .options
+            .max_payload_bytes
+            .unwrap_or(DEFAULT_MAX_PAYLOAD_BYTES);
+        if !exceeds_max_payload(sample, max_payload_bytes) {
+            return false;
+        }
+        let dropped = self.oversized_dropped.fetch_add(1, Ordering::Relaxed) + 1;
+        warn!(
+            "Dropping oversized sample on {} ({} bytes, {} dropped so far)",
+            sample.key_expr,
+            sample.value.payload.len(),
+            dropped
+        );
+        true
+    }
+
+    pub fn payload_size_snapshot(&self) -> HistogramSnapshot {
+        self.payload_sizes.snapshot()
+    }
+
+    pub fn config_latency_snapshot(&self) -> HistogramSnapshot {
+        self.config_latency.snapshot()
+    }
+
+    // Replace the whole config and let the interface re-initialize
+    pub async fn set_config(&self, new_config: NodeConfig) -> Result<()> {
+        self.set_config_from(new_config, "local").await
+    }
+
+    async fn set_config_from(&self, new_config: NodeConfig, source: &str) -> Result<()> {
+        let mut interface = self.interface.lock().await;
+        let mut config = self.config.write().await;
+        interface.set_config(new_config.clone()).await;
+        self.record_config_latency(&new_config);
+        let entry = self.audit_config_change(&config, &new_config, source);
+        *config = new_config.clone();
+        drop(interface);
+        self.persist_config(&new_config).await;
+        drop(config);
+        let _ = self.config_changes.send(new_config);
+        self.publish_audit_entry(entry).await;
+        Ok(())
+    }
+
+    // Merge `patch.config` into the current config and let the interface reconfigure
+    pub async fn update_config(&self, patch: NodeConfig) -> Result<()> {
+        self.update_config_from(patch, "local").await
+    }
+
+    async fn update_config_from(&self, patch: NodeConfig, source: &str) -> Result<()> {
+        let mut interface = self.interface.lock().await;
+        let mut config = self.config.write().await;
+        let mut merged = config.clone();
+        merged.merge(&patch.config);
+        interface.update_config(merged.clone()).await;
+        self.record_config_latency(&patch);
+        let entry = self.audit_config_change(&config, &merged, source);
+        *config = merged.clone();
+        drop(interface);
+        self.persist_config(&merged).await;
+        drop(config);
+        let _ = self.config_changes.send(merged);
+        self.publish_audit_entry(entry).await;
+        Ok(())
+    }
+
+    // Apply RFC 6902 operations to the current config; if any op fails, including a
+    // `test`, none are applied
+    pub async fn patch_config(&self, ops: &[PatchOp]) -> Result<()> {
+        self.patch_config_from(ops, "local").await
+    }
+
+    async fn patch_config_from(&self, ops: &[PatchOp], source: &str) -> Result<()> {
+        let mut interface = self.interface.lock().await;
+        let mut config = self.config.write().await;
+        let mut patched = config.clone();
+        json_patch::patch(&mut patched.config, ops)
+            .map_err(|e| FabricError::InvalidConfig(format!("JSON patch failed: {}", e)))?;
+        interface.update_config(patched.clone()).await;
+        let entry = self.audit_config_change(&config, &patched, source);
+        *config = patched.clone();
+        drop(interface);
+        self.persist_config(&patched).await;
+        drop(config);
+        let _ = self.config_changes.send(patched);
+        self.publish_audit_entry(entry).await;
+        Ok(())
+    }
+
+    // The config is already applied, so a failed save is only logged. Callers hold the
+    // config write lock so saves land on disk in the order configs were applied.
+    async fn persist_config(&self, config: &NodeConfig) {
+        let Some(path) = &self.options.config_path else {
+            return;
+        };
+        if let Err(e) = persistence::save(path, config).await {
+            warn!(
+                "Failed to persist config for node {} to {}: {:?}",
+                self.id,
+                path.display(),
+                e
+            );
+        }
+    }
+
+    fn audit_config_change(
+        &self,
+        old_config: &NodeConfig,
+        new_config: &NodeConfig,
+        source: &str,
+    ) -> AuditEntry {
+        let entry = AuditEntry {
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_millis() as u64),
+            old_hash: config_hash(old_config),
+            new_hash: config_hash(new_config),
+            source: source.to_string(),
+        };
+        self.audit.lock().unwrap().record(entry.clone());
+        entry
+    }
+
+    async fn publish_audit_entry(&self, entry: AuditEntry) {
+        if !self.options.publish_audit {
+            return;
+        }
+        let key_expr = self.key("audit");
+        let result = match serde_json::to_vec(&entry) {
+            Ok(payload) => self
+                .session
+                .put(&key_expr, payload)
+                .encoding(Encoding::APP_JSON)
+                .res()
+                .await
+                .map_err(FabricError::ZenohError),
+            Err(e) => Err(FabricError::SerdeJsonError(e)),
+        };
+        if let Err(e) = result {
+            warn!(
+                "Failed to publish audit entry for node {}: {:?}",
+                self.id, e
+            );
+        }
+    }
+
+    // Every config applied from now on, for application code outside the interface.
+    // A subscriber that falls too far behind skips the changes it missed.
+    pub fn subscribe_config_changes(&self) -> impl Stream<Item = NodeConfig> {
+        let node_id = self.id.clone();
+        futures::stream::unfold(self.config_changes.subscribe(), move |mut rx| {
+            let node_id = node_id.clone();
+            async move {
+                loop {
+                    match rx.recv().await {
+                        Ok(config) => return Some((config, rx)),
+                        Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                            warn!(
+                                "Config change subscriber for node {} skipped {} changes",
+                                node_id, skipped
+                            );
+                        }
+                        Err(broadcast::error::RecvError::Closed) => return None,
+                    }
+                }
+            }
+        })
+    }
+
+    pub fn config_audit(&self) -> Vec<AuditEntry> {
+        self.audit.lock().unwrap().entries()
+    }
+
+    async fn dispatch_event(&self, message: BroadcastMessage) {
+        info!("Node {} received event: {}", self.id, message.kind);
+        let mut interface = self.interface.lock().await;
+        if let Err(e) = interface
+            .handle_event(&message.kind, &message.payload_str())
+            .await
+        {
+            warn!(
+                "Node {} failed to handle event {}: {:?}",
+                self.id, message.kind, e
+            );
+        }
+    }
+
+    async fn reply_active_config(&self, query: zenoh::queryable::Query) {
+        let config = self.get_config().await;
+        match self.options.encoding.encode(&config) {
+            Ok(payload) => {
+                let key_expr = query.key_expr().clone();
+                let value = Value::from(payload).encoding(self.options.encoding.zenoh_encoding());
+                if let Err(e) = query.reply(Ok(Sample::new(key_expr, value))).res().await {
+                    warn!(
+                        "Failed to reply with active config for node {}: {}",
+                        self.id, e
+                    );
+                }
+            }
+            Err(e) => warn!(
+                "Failed to serialize active config for node {}: {}",
+                self.id, e
+            ),
+        }
+    }
+
+    // Serve the latest NodeData on <namespace>/<id>/state so an orchestrator that
+    // starts after this node doesn't have to wait for the next heartbeat
+    pub async fn declare_state_queryable(
+        &self,
+    ) -> Result<zenoh::queryable::Queryable<'static, ()>> {
+        let self_clone = self.clone();
+        self.session
+            .declare_queryable(keys::state_key(self.namespace(), &self.id))
+            .callback(move |query| {
+                let self_clone = self_clone.clone();
+                tokio::spawn(async move {
+                    self_clone.reply_state(query).await;
+                });
+            })
+            .res()
+            .await
+            .map_err(FabricError::ZenohError)
+    }
+
+    async fn reply_state(&self, query: zenoh::queryable::Query) {
+        let payload = match self
+            .status_data(self.heartbeat_status())
+            .await
+            .and_then(|node_data| self.encode_node_data(&node_data, self.options.encoding))
+        {
+            Ok(payload) => payload,
+            Err(e) => {
+                warn!("Failed to serialize state for node {}: {:?}", self.id, e);
+                return;
+            }
+        };
+        let key_expr = query.key_expr().clone();
+        let value = Value::from(payload).encoding(self.options.encoding.zenoh_encoding());
+        if let Err(e) = query.reply(Ok(Sample::new(key_expr, value))).res().await {
+            warn!("Failed to reply with state for node {}: {}", self.id, e);
+        }
+    }
+
+    pub async fn fetch_config(&self, timeout: Duration) -> Result<Option<NodeConfig>> {
+        let key_expr = self.key("config");
+        let replies = self
+            .session
+            .get(&key_expr)
+            .timeout(timeout)
+            .res()
+            .await
+            .map_err(FabricError::ZenohError)?;
+
+        while let Ok(reply) = replies.recv_async().await {
+            match reply.sample {
+                Ok(sample) if self.oversized(&sample) => {}
+                Ok(sample) => {
+                    let Some(config) = self.decode_sample::<NodeConfig>(&sample) else {
+                        continue;
+                    };
+                    info!("Node {} fetched configuration: {:?}", self.id, config);
+                    self.set_config_from(config.clone(), sample.key_expr.as_str())
+                        .await?;
+                    return Ok(Some(config));
+                }
+                Err(e) => {
+                    warn!(
+                        "Error reply while fetching config for node {}: {:?}",
+                        self.id, e
+                    );
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    // Seconds between ticks, read from the config's "sampling_rate"
+    pub async fn sampling_interval(&self) -> Option<Duration> {
+        self.config.read().await.config["sampling_rate"]
+            .as_f64()
+            .filter(|seconds| *seconds > 0.0)
+            .map(Duration::from_secs_f64)
+    }
+
+    pub async fn tick(&self) -> Result<()> {
+        let node_data = self
+            .interface
             .lock()
             .await
-            .update_config(new_config.clone())
-            .await;
-        // Update the Node's config field
-        let mut config = self.config.write().await;
-        *config = new_config;
+            .tick(std::time::SystemTime::now())
+            .await?;
+        let node_data = match node_data {
+            None if self.options.lightweight_heartbeat => {
+                Some(self.status_data(self.heartbeat_status()).await?)
+            }
+            node_data => node_data,
+        };
+        if let Some(node_data) = node_data {
+            let key_expr = self.key("data");
+            let payload = self.encode_node_data(&node_data, self.options.encoding)?;
+            self.session
+                .put(
+                    &key_expr,
+                    Value::from(payload).encoding(self.options.encoding.zenoh_encoding()),
+                )
+                .res()
+                .await
+                .map_err(FabricError::ZenohError)?;
+        }
         Ok(())
     }
 
@@ -154,10 +1052,62 @@ impl Node {
         &self.id
     }
 
+    pub fn namespace(&self) -> &str {
+        &self.options.namespace
+    }
+
+    fn key(&self, suffix: &str) -> String {
+        keys::node_key(self.namespace(), &self.id, suffix)
+    }
+
+    // zenoh 0.11 only closes a session by consuming it, so a node's session always has one
+    pub fn zid(&self) -> ZenohId {
+        self.session.zid()
+    }
+
+    // Groups are listed under the "groups" key of the node's config
+    pub async fn get_groups(&self) -> Vec<String> {
+        self.config.read().await.config["groups"]
+            .as_array()
+            .map(|groups| {
+                groups
+                    .iter()
+                    .filter_map(|group| group.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     pub fn get_type(&self) -> &str {
         &self.node_type
     }
 
+    pub fn with_rng<T>(&self, f: impl FnOnce(&mut StdRng) -> T) -> T {
+        f(&mut self.rng.lock().unwrap())
+    }
+
+    pub fn mode(&self) -> Option<String> {
+        self.state_machine
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|state_machine| state_machine.current())
+    }
+
+    pub fn transition(&self, to: &str) -> Result<()> {
+        match self.state_machine.lock().unwrap().as_ref() {
+            Some(state_machine) => {
+                state_machine.transition(to)?;
+                info!("Node {} transitioned to {}", self.id, to);
+                Ok(())
+            }
+            None => Err(FabricError::Other(format!(
+                "Node {} does not declare a state machine",
+                self.id
+            ))),
+        }
+    }
+
     pub async fn get_interface(&self) -> Result<Arc<Mutex<Box<dyn NodeInterface + Send + Sync>>>> {
         Ok(self.interface.clone())
     }
@@ -166,29 +1116,145 @@ impl Node {
         &mut self,
         interface: Box<dyn NodeInterface + Send + Sync>,
     ) -> Result<()> {
+        *self.state_machine.lock().unwrap() = interface.state_machine();
         *self.interface.lock().await = interface;
         Ok(())
     }
 
-    pub async fn update_status(&self, status: String) -> Result<()> {
-        let node_data = NodeData {
+    async fn run_self_test(&self) -> Result<()> {
+        let result = self.interface.lock().await.self_test().await;
+        let Err(e) = result else {
+            return Ok(());
+        };
+        let error = e.to_string();
+        match self.options.self_test_policy {
+            SelfTestPolicy::FailFast => {
+                error!("Node {} failed its self-test: {}", self.id, error);
+                let node_data = NodeData {
+                    node_id: self.id.clone(),
+                    node_type: self.node_type.clone(),
+                    status: NodeStatus::Failed,
+                    timestamp: current_timestamp()?,
+                    metadata: Some(serde_json::json!({ "error": error })),
+                    labels: self.options.labels.clone(),
+                };
+                self.publish_node_status(&node_data).await?;
+                Err(FabricError::SelfTestFailed(error))
+            }
+            SelfTestPolicy::Degraded => {
+                warn!(
+                    "Node {} failed its self-test, running degraded: {}",
+                    self.id, error
+                );
+                *self.self_test_error.write().unwrap() = Some(error);
+                Ok(())
+            }
+        }
+    }
+
+    fn heartbeat_status(&self) -> NodeStatus {
+        if self.self_test_error.read().unwrap().is_some() {
+            NodeStatus::Degraded
+        } else {
+            NodeStatus::Online
+        }
+    }
+
+    pub async fn update_status(&self, status: NodeStatus) -> Result<()> {
+        let node_data = self.status_data(status).await?;
+        self.publish_node_status(&node_data).await
+    }
+
+    async fn status_data(&self, status: NodeStatus) -> Result<NodeData> {
+        Ok(NodeData {
             node_id: self.id.clone(),
             node_type: self.node_type.clone(),
             status,
-            timestamp: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .map_err(|e| FabricError::Other(e.to_string()))?
-                .as_secs(),
-            metadata: None,
+            timestamp: current_timestamp()?,
+            metadata: self.status_metadata().await,
+            labels: self.options.labels.clone(),
+        })
+    }
+
+    async fn publish_heartbeat(&self, status: NodeStatus) -> Result<()> {
+        let heartbeat = Heartbeat {
+            id: self.id.clone(),
+            ts: current_timestamp()?,
+            status,
         };
-        self.publish_node_status(&node_data).await
+        let key_expr = keys::status_key(self.namespace(), &self.id);
+        let payload = heartbeat.encode(self.options.encoding, self.options.timestamp_format)?;
+        self.session
+            .put(
+                &key_expr,
+                Value::from(payload).encoding(self.options.encoding.zenoh_encoding()),
+            )
+            .res()
+            .await
+            .map_err(FabricError::ZenohError)?;
+        debug!("Published heartbeat for node {}: {:?}", self.id, heartbeat);
+        Ok(())
+    }
+
+    // Report offline and undeclare everything this node declared
+    pub async fn close(&self) -> Result<()> {
+        self.update_status(NodeStatus::Offline).await?;
+        for (_, publisher) in self.publishers.write().await.drain() {
+            publisher
+                .zenoh_publisher
+                .undeclare()
+                .res()
+                .await
+                .map_err(FabricError::ZenohError)?;
+        }
+        for (_, subscriber) in self.subscribers.write().await.drain() {
+            subscriber.zenoh_subscriber.undeclare().await?;
+        }
+        self.retained.write().await.clear();
+        info!("Node {} closed", self.id);
+        Ok(())
+    }
+
+    pub async fn get_metrics(&self) -> serde_json::Value {
+        self.interface.lock().await.metrics()
+    }
+
+    async fn status_metadata(&self) -> Option<serde_json::Value> {
+        let self_test_error = self.self_test_error.read().unwrap().clone();
+        match (self.get_metrics().await, self_test_error) {
+            (serde_json::Value::Object(mut metrics), Some(error)) => {
+                metrics.insert("error".to_string(), serde_json::Value::String(error));
+                Some(serde_json::Value::Object(metrics))
+            }
+            (_, Some(error)) => Some(serde_json::json!({ "error": error })),
+            (serde_json::Value::Object(metrics), None) if metrics.is_empty() => None,
+            (serde_json::Value::Null, None) => None,
+            (metrics, None) => Some(metrics),
+        }
+    }
+
+    // `value` with floats rounded per `float_significant_digits`
+    fn rounded(&self, mut value: serde_json::Value) -> serde_json::Value {
+        if let Some(significant_digits) = self.options.float_significant_digits {
+            round_floats(&mut value, significant_digits);
+        }
+        value
+    }
+
+    // Every NodeData this node sends goes through here, so status, state replies, tick
+    // data and telemetry are all rounded alike
+    fn encode_node_data(&self, node_data: &NodeData, encoding: PayloadEncoding) -> Result<Vec<u8>> {
+        encoding.encode(&self.rounded(node_data.to_value(self.options.timestamp_format)?))
     }
 
     async fn publish_node_status(&self, node_data: &NodeData) -> Result<()> {
-        let key_expr = format!("fabric/{}/status", self.id);
-        let payload = serde_json::to_vec(node_data).map_err(FabricError::SerdeJsonError)?;
+        let key_expr = keys::status_key(self.namespace(), &self.id);
+        let payload = self.encode_node_data(node_data, self.options.encoding)?;
         self.session
-            .put(&key_expr, payload)
+            .put(
+                &key_expr,
+                Value::from(payload).encoding(self.options.encoding.zenoh_encoding()),
+            )
             .res()
             .await
             .map_err(FabricError::ZenohError)?;
@@ -197,10 +1263,18 @@ impl Node {
     }
 
     pub async fn create_publisher(&self, topic: String) -> Result<()> {
+        self.create_publisher_with_options(topic, PublisherOptions::default())
+            .await
+    }
+
+    pub async fn create_publisher_with_options(
+        &self,
+        topic: String,
+        options: PublisherOptions,
+    ) -> Result<()> {
         let key_expr = topic.clone();
-        let zenoh_publisher = self
-            .session
-            .declare_publisher(key_expr)
+        let zenoh_publisher = options
+            .apply(self.session.declare_publisher(key_expr))
             .res()
             .await
             .map_err(FabricError::ZenohError)?;
@@ -216,20 +1290,186 @@ impl Node {
     }
 
     pub async fn publish(&self, topic: &str, data: Vec<u8>) -> Result<()> {
+        self.publish_value(topic, Value::from(data)).await
+    }
+
+    // Like publish, but with priority and congestion control for this sample only,
+    // overriding what the topic's publisher was declared with
+    pub async fn publish_with_options(
+        &self,
+        topic: &str,
+        data: Vec<u8>,
+        options: &PublisherOptions,
+    ) -> Result<()> {
+        let publishers = self.publishers.read().await;
+        let publisher = publishers
+            .get(topic)
+            .ok_or_else(|| FabricError::PublisherNotFound(topic.to_string()))?;
+        let value = Value::from(data);
+        self.payload_sizes.record(value.payload.len() as u64);
+        self.session
+            .put(publisher.zenoh_publisher.key_expr().clone(), value)
+            .priority(options.priority())
+            .congestion_control(options.congestion_control)
+            .res()
+            .await
+            .map_err(FabricError::ZenohError)?;
+        Ok(())
+    }
+
+    // Like publish, but declares and caches the publisher on first use
+    pub async fn publish_auto(&self, topic: &str, data: Vec<u8>) -> Result<()> {
+        if !self.has_publisher(topic).await {
+            self.create_publisher(topic.to_string()).await?;
+        }
+        self.publish(topic, data).await
+    }
+
+    // Publish `value` as JSON on `topic`, plus a transcoded copy on the negotiated key of
+    // every encoding that currently has subscribers
+    pub async fn publish_negotiated(&self, topic: &str, value: &serde_json::Value) -> Result<()> {
+        let value = &self.rounded(value.clone());
+        self.publish_auto(topic, PayloadEncoding::Json.encode(value)?)
+            .await?;
+        for encoding in PayloadEncoding::ALL {
+            let key = negotiated_key(topic, encoding);
+            if !self.has_publisher(&key).await {
+                self.create_publisher(key.clone()).await?;
+            }
+            if !self.has_matching_subscribers(&key).await {
+                continue;
+            }
+            let payload = encoding.encode(value)?;
+            self.publish_value(
+                &key,
+                Value::from(payload).encoding(encoding.zenoh_encoding()),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    // Subscribe to `topic` in the consumer's preferred encoding
+    pub async fn create_subscriber_with_encoding(
+        &self,
+        topic: String,
+        encoding: PayloadEncoding,
+        callback: Arc<Mutex<dyn Fn(Sample) + Send + Sync>>,
+    ) -> Result<()> {
+        self.create_subscriber(negotiated_key(&topic, encoding), callback)
+            .await
+    }
+
+    async fn has_matching_subscribers(&self, topic: &str) -> bool {
+        let publishers = self.publishers.read().await;
+        let Some(publisher) = publishers.get(topic) else {
+            return false;
+        };
+        publisher
+            .zenoh_publisher
+            .matching_status()
+            .res()
+            .await
+            .is_ok_and(|status| status.matching_subscribers())
+    }
+
+    pub async fn has_publisher(&self, topic: &str) -> bool {
+        self.publishers.read().await.contains_key(topic)
+    }
+
+    pub async fn publish_json(&self, topic: &str, value: serde_json::Value) -> Result<()> {
+        let mut metadata = value;
+        if let (Some(object), serde_json::Value::Object(metrics)) =
+            (metadata.as_object_mut(), self.get_metrics().await)
+        {
+            for (key, metric) in metrics {
+                object.entry(key).or_insert(metric);
+            }
+        }
+        let node_data = NodeData {
+            node_id: self.id.clone(),
+            node_type: self.node_type.clone(),
+            status: NodeStatus::Online,
+            timestamp: current_timestamp()?,
+            metadata: Some(metadata),
+            labels: self.options.labels.clone(),
+        };
+        let payload = self.encode_node_data(&node_data, PayloadEncoding::Json)?;
+        self.publish_value(topic, Value::from(payload).encoding(Encoding::APP_JSON))
+            .await
+    }
+
+    #[cfg(feature = "protobuf")]
+    pub async fn publish_protobuf(&self, topic: &str, node_data: &NodeData) -> Result<()> {
+        let payload = node_data.to_protobuf()?;
+        self.publish_value(
+            topic,
+            Value::from(payload).encoding(Encoding::from(crate::node::protobuf::PROTOBUF_ENCODING)),
+        )
+        .await
+    }
+
+    // Publish and keep serving the last value to late joiners via a queryable on the topic
+    pub async fn publish_retained(&self, topic: &str, data: Vec<u8>) -> Result<()> {
+        let mut retained = self.retained.write().await;
+        if let Some(retained_topic) = retained.get(topic) {
+            *retained_topic.value.write().unwrap() = data.clone();
+        } else {
+            let value = Arc::new(std::sync::RwLock::new(data.clone()));
+            let value_clone = value.clone();
+            let key_expr = KeyExpr::try_from(topic.to_string()).map_err(FabricError::ZenohError)?;
+            let reply_key_expr = key_expr.clone();
+            let queryable = self
+                .session
+                .declare_queryable(key_expr)
+                .callback(move |query| {
+                    let payload = value_clone.read().unwrap().clone();
+                    let reply_key_expr = reply_key_expr.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = query
+                            .reply(Ok(Sample::new(reply_key_expr, payload)))
+                            .res()
+                            .await
+                        {
+                            warn!("Failed to reply with retained value: {}", e);
+                        }
+                    });
+                })
+                .res()
+                .await
+                .map_err(FabricError::ZenohError)?;
+            retained.insert(
+                topic.to_string(),
+                RetainedTopic {
+                    value,
+                    _queryable: queryable,
+                },
+            );
+            debug!("Declared retained queryable for topic: {}", topic);
+        }
+        drop(retained);
+
+        self.session
+            .put(topic, data)
+            .res()
+            .await
+            .map_err(FabricError::ZenohError)?;
+        Ok(())
+    }
+
+    async fn publish_value(&self, topic: &str, value: Value) -> Result<()> {
         let publishers = self.publishers.read().await;
         if let Some(publisher) = publishers.get(topic) {
+            self.payload_sizes.record(value.payload.len() as u64);
             publisher
                 .zenoh_publisher
-                .put(data)
+                .put(value)
                 .res()
                 .await
                 .map_err(FabricError::ZenohError)?;
             Ok(())
         } else {
-            Err(FabricError::Other(format!(
-                "Publisher not found for topic: {}",
-                topic
-            )))
+            Err(FabricError::PublisherNotFound(topic.to_string()))
         }
     }
 
@@ -237,28 +1477,72 @@ impl Node {
         &self,
         topic: String,
         callback: Arc<Mutex<dyn Fn(Sample) + Send + Sync>>,
+    ) -> Result<()> {
+        self.create_subscriber_with_options(topic, callback, SubscriberOptions::default())
+            .await
+    }
+
+    pub async fn create_subscriber_with_options(
+        &self,
+        topic: String,
+        callback: Arc<Mutex<dyn Fn(Sample) + Send + Sync>>,
+        options: SubscriberOptions,
     ) -> Result<()> {
         let key_expr = topic.clone();
         let subscriber_tx = self.subscriber_tx.clone();
-        let zenoh_subscriber = self
-            .session
-            .declare_subscriber(&key_expr)
-            .callback(move |sample| {
-                let tx = subscriber_tx.clone();
-                tokio::spawn(async move {
-                    if let Err(e) = tx.send(sample).await {
-                        error!("Failed to send sample to handler: {:?}", e);
+        let oversized_dropped = self.oversized_dropped.clone();
+        let ingest_dropped = self.ingest_dropped.clone();
+        let max_payload_bytes = options.max_payload_bytes;
+        let zenoh_subscriber =
+            ZenohSubscriber::declare(&self.session, &key_expr, options.mode, move |sample| {
+                if exceeds_max_payload(&sample, max_payload_bytes) {
+                    let dropped = oversized_dropped.fetch_add(1, Ordering::Relaxed) + 1;
+                    warn!(
+                        "Dropping oversized sample on {} ({} bytes, {} dropped so far)",
+                        sample.key_expr,
+                        sample.value.payload.len(),
+                        dropped
+                    );
+                    return;
+                }
+                // Forward inline: a task per sample could reach the handler out of order
+                match subscriber_tx.try_send(sample) {
+                    Ok(()) => {}
+                    Err(mpsc::error::TrySendError::Full(sample)) => {
+                        let dropped = ingest_dropped.fetch_add(1, Ordering::Relaxed) + 1;
+                        if dropped % 100 == 1 {
+                            warn!(
+                                "Ingest queue full, dropped sample on {} ({} dropped so far)",
+                                sample.key_expr, dropped
+                            );
+                        }
                     }
-                });
+                    Err(mpsc::error::TrySendError::Closed(_)) => {
+                        error!("Failed to send sample to handler: channel closed");
+                    }
+                }
             })
-            .res()
-            .await
-            .map_err(FabricError::ZenohError)?;
+            .await?;
+
+        let (dispatch_tx, mut dispatch_rx) = mpsc::channel::<Sample>(
+            self.options
+                .ingest_capacity
+                .unwrap_or(DEFAULT_INGEST_CAPACITY),
+        );
+        tokio::spawn(async move {
+            while let Some(sample) = dispatch_rx.recv().await {
+                let callback = callback.lock().await;
+                callback(sample);
+            }
+        });
 
         let subscriber = Subscriber {
             topic: topic.clone(),
-            callback,
+            dispatch_tx,
             zenoh_subscriber,
+            dedup: options
+                .dedup_window
+                .map(|window| std::sync::Mutex::new(Deduplicator::new(window))),
         };
 
         debug!("Created subscriber for topic: {}", subscriber.topic);
@@ -267,17 +1551,62 @@ impl Node {
         Ok(())
     }
 
+    // Samples routed to the subscriber before removal are still delivered; later
+    // ones, even if already queued for routing, no longer match it
+    pub async fn unsubscribe_from_topic(&self, topic: &str) -> Result<()> {
+        let subscriber = self
+            .subscribers
+            .write()
+            .await
+            .remove(topic)
+            .ok_or_else(|| {
+                FabricError::Other(format!("Subscriber not found for topic: {}", topic))
+            })?;
+        subscriber.zenoh_subscriber.undeclare().await?;
+        debug!("Removed subscriber for topic: {}", topic);
+        Ok(())
+    }
+
+    // Fetch the latest sample per key held for a pull-mode subscriber; it reaches the
+    // subscriber's callback like a pushed sample would
+    pub async fn pull_next(&self, topic: &str) -> Result<()> {
+        let subscribers = self.subscribers.read().await;
+        let subscriber = subscribers.get(topic).ok_or_else(|| {
+            FabricError::Other(format!("Subscriber not found for topic: {}", topic))
+        })?;
+        subscriber.zenoh_subscriber.pull().await
+    }
+
     async fn handle_subscriber_samples(&self, mut rx: mpsc::Receiver<Sample>) {
         while let Some(sample) = rx.recv().await {
-            let subscribers = self.subscribers.read().await;
-            for subscriber in subscribers.values() {
-                if subscriber
-                    .zenoh_subscriber
-                    .key_expr()
-                    .intersects(sample.key_expr.as_keyexpr())
-                {
-                    let callback = subscriber.callback.lock().await;
-                    callback(sample.clone());
+            // Route under the lock, deliver after releasing it so a slow callback
+            // holds up neither other subscribers nor create/unsubscribe
+            let targets: Vec<_> = {
+                let subscribers = self.subscribers.read().await;
+                subscribers
+                    .values()
+                    .filter(|subscriber| subscriber.accepts(&sample))
+                    .map(|subscriber| subscriber.dispatch_tx.clone())
+                    .collect()
+            };
+            for dispatch_tx in targets {
+                match dispatch_tx.try_send(sample.clone()) {
+                    Ok(()) => {}
+                    Err(mpsc::error::TrySendError::Full(sample)) => {
+                        let dropped = self.ingest_dropped.fetch_add(1, Ordering::Relaxed) + 1;
+                        if dropped % 100 == 1 {
+                            warn!(
+                                "Subscriber queue full, dropped sample on {} ({} dropped so far)",
+                                sample.key_expr, dropped
+                            );
+                        }
+                    }
+                    Err(mpsc::error::TrySendError::Closed(sample)) => {
+                        debug!(
+                            "Dropping sample on {} for a closed subscriber",
+                            sample.key_expr
+                        );
+                    }
                 }
             }
         }
@@ -285,3 +1614,79 @@ impl Node {
 
     // Remove the old handle_subscriber_samples method
 }
+
+pub struct NodeHandle {
+    node_id: String,
+    cancel: CancellationToken,
+    task: tokio::task::JoinHandle<Result<()>>,
+}
+
+impl NodeHandle {
+    pub fn node_id(&self) -> &str {
+        &self.node_id
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+
+    // Cancel the node and wait for `run` to return, surfacing its error or panic
+    pub async fn shutdown(self) -> Result<()> {
+        self.cancel.cancel();
+        match tokio::time::timeout(NODE_SHUTDOWN_TIMEOUT, self.task).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(e)) => Err(FabricError::Other(format!(
+                "Node {} task error: {}",
+                self.node_id, e
+            ))),
+            Err(_) => Err(FabricError::Other(format!(
+                "Node {} did not stop within {:?}",
+                self.node_id, NODE_SHUTDOWN_TIMEOUT
+            ))),
+        }
+    }
+}
+
+#[async_trait]
+impl Component for Node {
+    fn name(&self) -> String {
+        format!("node {}", self.id)
+    }
+
+    async fn close(&self) -> Result<()> {
+        Node::close(self).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn retry_config() -> RetryConfig {
+        RetryConfig {
+            initial_interval: Duration::from_millis(10),
+            randomization_factor: 0.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_subscriber_retry_gives_up_after_max_failures() {
+        let mut retry = SubscriberRetry::new(&retry_config(), 3);
+        assert_eq!(retry.failed(), Some(Duration::from_millis(10)));
+        assert_eq!(retry.failed(), Some(Duration::from_millis(20)));
+        assert_eq!(retry.failed(), None);
+        assert_eq!(retry.failures, 3);
+    }
+
+    #[test]
+    fn test_subscriber_retry_resets_on_success() {
+        let mut retry = SubscriberRetry::new(&retry_config(), 3);
+        retry.failed();
+        retry.failed();
+        retry.succeeded();
+        assert_eq!(retry.failures, 0);
+        assert_eq!(retry.failed(), Some(Duration::from_millis(10)));
+        assert_eq!(retry.failed(), Some(Duration::from_millis(20)));
+    }
+}
@@ -0,0 +1,88 @@
+use crate::error::Result;
+use crate::node::interface::NodeConfig;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PersistedConfig {
+    // Milliseconds since the Unix epoch
+    pub saved_at: u64,
+    pub config: NodeConfig,
+}
+
+// Saves to one path must be serialized by the caller, or an older config can be
+// renamed into place after a newer one
+pub async fn save(path: &Path, config: &NodeConfig) -> Result<()> {
+    let persisted = PersistedConfig {
+        saved_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis() as u64),
+        config: config.clone(),
+    };
+    write_atomic(path, &serde_json::to_vec_pretty(&persisted)?).await
+}
+
+// Write to a uniquely named sibling file and rename it over `path`, so a crash
+// mid-write can't leave a truncated file and concurrent writers never share one
+pub(crate) async fn write_atomic(path: &Path, bytes: &[u8]) -> Result<()> {
+    static NEXT_TMP: AtomicU64 = AtomicU64::new(0);
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(format!(
+        ".{}.{}.tmp",
+        std::process::id(),
+        NEXT_TMP.fetch_add(1, Ordering::Relaxed)
+    ));
+    let tmp_path = path.with_file_name(tmp_name);
+    let result = match tokio::fs::write(&tmp_path, bytes).await {
+        Ok(()) => tokio::fs::rename(&tmp_path, path).await,
+        Err(e) => Err(e),
+    };
+    if result.is_err() {
+        let _ = tokio::fs::remove_file(&tmp_path).await;
+    }
+    Ok(result?)
+}
+
+// None when there is nothing usable on disk; a corrupt file is reported and skipped
+pub async fn load(path: &Path) -> Option<PersistedConfig> {
+    let bytes = match tokio::fs::read(path).await {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+        Err(e) => {
+            warn!("Failed to read persisted config {}: {}", path.display(), e);
+            return None;
+        }
+    };
+    match serde_json::from_slice(&bytes) {
+        Ok(persisted) => Some(persisted),
+        Err(e) => {
+            warn!(
+                "Ignoring corrupt persisted config {}: {}",
+                path.display(),
+                e
+            );
+            None
+        }
+    }
+}
+
+// The persisted config wins unless the passed one carries a newer epoch-millis
+// "timestamp", the same stamp used for apply latency
+pub fn newest(passed: NodeConfig, persisted: Option<PersistedConfig>) -> NodeConfig {
+    let Some(persisted) = persisted else {
+        return passed;
+    };
+    if persisted.config.node_id != passed.node_id {
+        warn!(
+            "Ignoring persisted config for {} while starting {}",
+            persisted.config.node_id, passed.node_id
+        );
+        return passed;
+    }
+    match passed.config["timestamp"].as_u64() {
+        Some(stamped_at) if stamped_at > persisted.saved_at => passed,
+        _ => persisted.config,
+    }
+}
@@ -0,0 +1,59 @@
+use crate::error::Result;
+use crate::node::interface::NodeData;
+use prost::Message;
+use std::collections::HashMap;
+
+pub const PROTOBUF_ENCODING: &str = "application/protobuf";
+
+// Mirrors proto/node_data.proto
+#[derive(Clone, PartialEq, Message)]
+pub struct NodeDataProto {
+    #[prost(string, tag = "1")]
+    pub node_id: String,
+    #[prost(string, tag = "2")]
+    pub node_type: String,
+    #[prost(uint64, tag = "3")]
+    pub timestamp: u64,
+    #[prost(string, optional, tag = "4")]
+    pub metadata_json: Option<String>,
+    #[prost(string, tag = "5")]
+    pub status: String,
+    #[prost(map = "string, string", tag = "6")]
+    pub labels: HashMap<String, String>,
+}
+
+impl NodeData {
+    pub fn to_protobuf(&self) -> Result<Vec<u8>> {
+        let metadata_json = self
+            .metadata
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()?;
+        let message = NodeDataProto {
+            node_id: self.node_id.clone(),
+            node_type: self.node_type.clone(),
+            timestamp: self.timestamp,
+            metadata_json,
+            status: self.status.to_string(),
+            labels: self.labels.clone(),
+        };
+        Ok(message.encode_to_vec())
+    }
+
+    pub fn from_protobuf(bytes: &[u8]) -> Result<Self> {
+        let message = NodeDataProto::decode(bytes)?;
+        let metadata = message
+            .metadata_json
+            .as_deref()
+            .map(serde_json::from_str)
+            .transpose()?;
+        Ok(NodeData {
+            node_id: message.node_id,
+            node_type: message.node_type,
+            timestamp: message.timestamp,
+            metadata,
+            status: message.status.parse().unwrap_or_default(),
+            labels: message.labels,
+        })
+    }
+}
@@ -0,0 +1,54 @@
+use crate::error::{FabricError, Result};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+// Clones share the current state, so the copy a node takes from its interface and the
+// one the interface keeps always agree on the mode
+#[derive(Clone, Debug)]
+pub struct StateMachine {
+    current: Arc<Mutex<String>>,
+    transitions: Arc<HashMap<String, HashSet<String>>>,
+}
+
+impl StateMachine {
+    pub fn new(initial: &str) -> Self {
+        Self {
+            current: Arc::new(Mutex::new(initial.to_string())),
+            transitions: Arc::new(HashMap::new()),
+        }
+    }
+
+    pub fn allow(mut self, from: &str, to: &[&str]) -> Self {
+        Arc::make_mut(&mut self.transitions)
+            .entry(from.to_string())
+            .or_default()
+            .extend(to.iter().map(|state| state.to_string()));
+        self
+    }
+
+    pub fn current(&self) -> String {
+        self.current.lock().unwrap().clone()
+    }
+
+    pub fn can_transition(&self, to: &str) -> bool {
+        self.allows(&self.current.lock().unwrap(), to)
+    }
+
+    pub fn transition(&self, to: &str) -> Result<()> {
+        let mut current = self.current.lock().unwrap();
+        if !self.allows(&current, to) {
+            return Err(FabricError::InvalidTransition(
+                current.clone(),
+                to.to_string(),
+            ));
+        }
+        *current = to.to_string();
+        Ok(())
+    }
+
+    fn allows(&self, from: &str, to: &str) -> bool {
+        self.transitions
+            .get(from)
+            .is_some_and(|allowed| allowed.contains(to))
+    }
+}
@@ -0,0 +1,76 @@
+use crate::histogram::{Histogram, HistogramSnapshot};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+#[derive(Default)]
+pub struct Metrics {
+    pub ingest_dropped: AtomicU64,
+    pub oversized_dropped: AtomicU64,
+    pub paused_dropped: AtomicU64,
+    pub commands_expired: AtomicU64,
+    pub disallowed_type_dropped: AtomicU64,
+    pub status_updates_total: AtomicU64,
+    pub configs_published_total: AtomicU64,
+    // Nodes marked offline for missing their heartbeat
+    pub offline_transitions_total: AtomicU64,
+    pub config_publishes_in_flight: AtomicU64,
+    // Most config publishes ever in flight at once
+    pub config_publishes_peak: AtomicU64,
+    pub published_payload_bytes: Histogram,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MetricsSnapshot {
+    pub ingest_dropped: u64,
+    pub oversized_dropped: u64,
+    pub paused_dropped: u64,
+    pub commands_expired: u64,
+    pub disallowed_type_dropped: u64,
+    pub status_updates_total: u64,
+    pub configs_published_total: u64,
+    pub offline_transitions_total: u64,
+    pub config_publishes_peak: u64,
+    // Gauges over the node table at snapshot time
+    pub nodes_online: usize,
+    pub nodes_offline: usize,
+    pub published_payload_bytes: HistogramSnapshot,
+}
+
+impl Metrics {
+    // Counts a config publish as in flight until the guard drops
+    pub fn begin_config_publish(&self) -> ConfigPublishGuard<'_> {
+        let in_flight = self
+            .config_publishes_in_flight
+            .fetch_add(1, Ordering::SeqCst)
+            + 1;
+        self.config_publishes_peak
+            .fetch_max(in_flight, Ordering::SeqCst);
+        ConfigPublishGuard(self)
+    }
+
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        MetricsSnapshot {
+            ingest_dropped: self.ingest_dropped.load(Ordering::Relaxed),
+            oversized_dropped: self.oversized_dropped.load(Ordering::Relaxed),
+            paused_dropped: self.paused_dropped.load(Ordering::Relaxed),
+            commands_expired: self.commands_expired.load(Ordering::Relaxed),
+            disallowed_type_dropped: self.disallowed_type_dropped.load(Ordering::Relaxed),
+            status_updates_total: self.status_updates_total.load(Ordering::Relaxed),
+            configs_published_total: self.configs_published_total.load(Ordering::Relaxed),
+            offline_transitions_total: self.offline_transitions_total.load(Ordering::Relaxed),
+            config_publishes_peak: self.config_publishes_peak.load(Ordering::Relaxed),
+            nodes_online: 0,
+            nodes_offline: 0,
+            published_payload_bytes: self.published_payload_bytes.snapshot(),
+        }
+    }
+}
+
+pub struct ConfigPublishGuard<'a>(&'a Metrics);
+
+impl Drop for ConfigPublishGuard<'_> {
+    fn drop(&mut self) {
+        self.0
+            .config_publishes_in_flight
+            .fetch_sub(1, Ordering::SeqCst);
+    }
+}
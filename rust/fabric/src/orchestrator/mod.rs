@@ -1,14 +1,38 @@
+mod metrics;
 #[allow(clippy::module_inception)]
 mod orchestrator;
+pub use metrics::{Metrics, MetricsSnapshot};
 pub use orchestrator::Orchestrator;
 
-use crate::node::interface::{NodeConfig, NodeData};
+use crate::node::interface::{NodeConfig, NodeData, NodeStatus};
+use crate::payload::PayloadEncoding;
+use backoff::{backoff::Backoff, ExponentialBackoff, ExponentialBackoffBuilder};
+use log::warn;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::future::Future;
+use std::time::Duration;
 
-#[derive(Debug, Clone)]
+pub const OFFLINE_REASON_HEARTBEAT_TIMEOUT: &str = "heartbeat timeout";
+pub const OFFLINE_REASON_EXPLICIT: &str = "explicit offline";
+pub const OFFLINE_REASON_LIVELINESS_LOST: &str = "liveliness lost";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NodeState {
     pub last_value: crate::node::interface::NodeData,
+    #[serde(with = "crate::timestamp::epoch_millis")]
     pub last_update: std::time::SystemTime,
+    pub offline_reason: Option<String>,
+    // When the current status was first observed
+    #[serde(with = "crate::timestamp::epoch_millis")]
+    pub status_since: std::time::SystemTime,
+    // Last status delivered to callbacks
+    pub reported_status: Option<NodeStatus>,
+    // Distance between the node's reported timestamp and our receive time
+    pub clock_skew: Option<Duration>,
+    // Set while clock_skew exceeds the configured threshold
+    pub clock_skewed: bool,
 }
 
 impl NodeState {
@@ -16,8 +40,60 @@ impl NodeState {
         Self {
             last_value: node_data,
             last_update: std::time::SystemTime::now(),
+            offline_reason: None,
+            status_since: std::time::SystemTime::now(),
+            reported_status: None,
+            clock_skew: None,
+            clock_skewed: false,
         }
     }
+
+    pub fn update(&mut self, node_data: NodeData) {
+        let previous_status = std::mem::replace(&mut self.last_value, node_data).status;
+        let now = std::time::SystemTime::now();
+        if previous_status != self.last_value.status {
+            self.status_since = now;
+        }
+        self.last_update = now;
+    }
+
+    pub fn set_status(&mut self, status: NodeStatus) {
+        if self.last_value.status != status {
+            self.last_value.status = status;
+            self.status_since = std::time::SystemTime::now();
+        }
+    }
+
+    pub fn has_pending_status(&self) -> bool {
+        self.reported_status
+            .as_ref()
+            .is_some_and(|reported| *reported != self.last_value.status)
+    }
+
+    // NodeData handed to callbacks, carrying the offline reason in its metadata
+    pub fn callback_data(&self) -> NodeData {
+        let mut node_data = self.last_value.clone();
+        if let Some(reason) = &self.offline_reason {
+            let metadata = node_data
+                .metadata
+                .get_or_insert_with(|| serde_json::json!({}));
+            if let Some(object) = metadata.as_object_mut() {
+                object.insert(
+                    "offline_reason".to_string(),
+                    serde_json::Value::String(reason.clone()),
+                );
+            }
+        }
+        node_data
+    }
+}
+
+// Who the orchestrator's session is connected to, by zenoh id
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkSnapshot {
+    pub zid: String,
+    pub routers: Vec<String>,
+    pub peers: Vec<String>,
 }
 
 pub type CallbackFunction = Box<dyn Fn(NodeData) + Send + Sync>;
@@ -25,6 +101,218 @@ pub type CallbackFunction = Box<dyn Fn(NodeData) + Send + Sync>;
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct OrchestratorConfig {
     pub nodes: Vec<NodeConfig>,
+    // Re-publish the last config to nodes that come back online
+    #[serde(default)]
+    pub reconcile_on_reconnect: bool,
+    #[serde(default)]
+    pub publish_retry: RetryConfig,
+    // Status and subscriber samples arriving while this many are already queued for
+    // their handler are dropped
+    #[serde(default = "default_ingest_capacity")]
+    pub ingest_capacity: usize,
+    // Status updates and query replies larger than this are dropped unread
+    #[serde(default = "default_max_payload_bytes")]
+    pub max_payload_bytes: usize,
+    // Time after startup during which no node is marked offline
+    #[serde(default)]
+    pub offline_grace_period: Duration,
+    // How long a new status must hold before callbacks hear about it
+    #[serde(default)]
+    pub status_debounce: Duration,
+    // Longest a health sweep waits on one node's status reply
+    #[serde(default = "default_health_query_timeout")]
+    pub health_query_timeout: Duration,
+    // Periodically re-publish `nodes` configs to nodes whose active config drifted
+    #[serde(default)]
+    pub reconcile_interval: Option<Duration>,
+    // Commands queued for offline nodes are dropped once older than this
+    #[serde(default = "default_command_ttl")]
+    pub command_ttl: Duration,
+    // Elect a single leader among orchestrators; only the leader pushes configs
+    #[serde(default)]
+    pub leader_election: bool,
+    // Online nodes silent for longer than this are marked offline
+    #[serde(default = "default_offline_timeout")]
+    pub offline_timeout: Duration,
+    // How often nodes are checked against offline_timeout
+    #[serde(default = "default_health_check_interval")]
+    pub health_check_interval: Duration,
+    // Encoding of published configs; incoming statuses are decoded by their declared encoding
+    #[serde(default)]
+    pub encoding: PayloadEncoding,
+    // Nodes whose clocks are further than this from ours are flagged
+    #[serde(default = "default_clock_skew_threshold")]
+    pub clock_skew_threshold: Duration,
+    // Merge incoming metadata into the stored metadata instead of replacing it,
+    // for nodes that only publish what changed
+    #[serde(default)]
+    pub merge_metadata: bool,
+    // Statuses from nodes of any other type are ignored; empty allows every type
+    #[serde(default)]
+    pub allowed_node_types: HashSet<String>,
+    // Stop run once no node status has arrived for this long, for orchestrators
+    // spun up for a single task
+    #[serde(default)]
+    pub idle_timeout: Option<Duration>,
+    // Most config publishes in flight at once across all callers; unbounded when unset
+    #[serde(default)]
+    pub max_concurrent_publishes: Option<usize>,
+    // First chunk of every key expression; orchestrators only see nodes in their
+    // namespace
+    #[serde(default = "default_namespace")]
+    pub namespace: String,
+}
+
+fn default_namespace() -> String {
+    crate::keys::DEFAULT_NAMESPACE.to_string()
+}
+
+fn default_ingest_capacity() -> usize {
+    100
+}
+
+fn default_max_payload_bytes() -> usize {
+    crate::subscriber::DEFAULT_MAX_PAYLOAD_BYTES
+}
+
+fn default_health_query_timeout() -> Duration {
+    Duration::from_secs(2)
+}
+
+fn default_command_ttl() -> Duration {
+    Duration::from_secs(60)
+}
+
+fn default_offline_timeout() -> Duration {
+    Duration::from_secs(10)
+}
+
+fn default_health_check_interval() -> Duration {
+    Duration::from_secs(1)
+}
+
+fn default_clock_skew_threshold() -> Duration {
+    Duration::from_secs(5)
+}
+
+// 64-bit FNV-1a. Rollout cohorts must not move between builds, so this can't be
+// std's DefaultHasher, whose algorithm may change across Rust releases.
+fn rollout_rank(node_id: &str) -> u64 {
+    node_id.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+// The `percent` of `candidates` a rollout reaches, rounded up. A node's rank depends
+// only on its id, so a larger rollout of the same nodes includes every node a
+// smaller one did.
+fn rollout_cohort(mut candidates: Vec<String>, percent: u8) -> Vec<String> {
+    candidates.sort_by_cached_key(|node_id| (rollout_rank(node_id), node_id.clone()));
+    let count = (candidates.len() * percent as usize).div_ceil(100);
+    candidates.truncate(count);
+    candidates
+}
+
+impl Default for OrchestratorConfig {
+    fn default() -> Self {
+        Self {
+            nodes: Vec::new(),
+            reconcile_on_reconnect: false,
+            publish_retry: RetryConfig::default(),
+            ingest_capacity: default_ingest_capacity(),
+            max_payload_bytes: default_max_payload_bytes(),
+            offline_grace_period: Duration::ZERO,
+            status_debounce: Duration::ZERO,
+            health_query_timeout: default_health_query_timeout(),
+            reconcile_interval: None,
+            command_ttl: default_command_ttl(),
+            leader_election: false,
+            offline_timeout: default_offline_timeout(),
+            health_check_interval: default_health_check_interval(),
+            clock_skew_threshold: default_clock_skew_threshold(),
+            encoding: PayloadEncoding::default(),
+            merge_metadata: false,
+            allowed_node_types: HashSet::new(),
+            idle_timeout: None,
+            max_concurrent_publishes: None,
+            namespace: default_namespace(),
+        }
+    }
+}
+
+// Backoff used when publishing configs; jitter keeps concurrent retries from synchronizing
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryConfig {
+    pub initial_interval: Duration,
+    pub max_interval: Duration,
+    pub max_elapsed_time: Duration,
+    pub multiplier: f64,
+    pub randomization_factor: f64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            initial_interval: Duration::from_millis(100),
+            max_interval: Duration::from_secs(5),
+            max_elapsed_time: Duration::from_secs(30),
+            multiplier: 2.0,
+            randomization_factor: 0.5,
+        }
+    }
+}
+
+impl RetryConfig {
+    pub fn backoff(&self) -> ExponentialBackoff {
+        ExponentialBackoffBuilder::new()
+            .with_initial_interval(self.initial_interval)
+            .with_max_interval(self.max_interval)
+            .with_max_elapsed_time(Some(self.max_elapsed_time))
+            .with_multiplier(self.multiplier)
+            .with_randomization_factor(self.randomization_factor)
+            .build()
+    }
+
+    pub async fn retry<T, E, F, Fut>(&self, description: &str, operation: F) -> Result<T, E>
+    where
+        E: Display,
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        self.retry_if(description, operation, |_| true).await
+    }
+
+    // Like retry, but errors rejected by `is_retryable` fail immediately
+    pub async fn retry_if<T, E, F, Fut>(
+        &self,
+        description: &str,
+        mut operation: F,
+        is_retryable: impl Fn(&E) -> bool,
+    ) -> Result<T, E>
+    where
+        E: Display,
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let mut backoff = self.backoff();
+        loop {
+            match operation().await {
+                Ok(value) => return Ok(value),
+                Err(err) if !is_retryable(&err) => return Err(err),
+                Err(err) => match backoff.next_backoff() {
+                    Some(duration) => {
+                        warn!(
+                            "Failed to {}, retrying in {:?}: {}",
+                            description, duration, err
+                        );
+                        tokio::time::sleep(duration).await;
+                    }
+                    None => return Err(err),
+                },
+            }
+        }
+    }
 }
 
 // Move the Orchestrator implementation here (if it's not already in the orchestrator.rs file)
@@ -36,21 +324,95 @@ impl Orchestrator {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::FabricError;
     use crate::node::interface::NodeData;
 
+    #[test]
+    fn test_rollout_cohort_is_pinned() {
+        assert_eq!(rollout_rank(""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(rollout_rank("a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(rollout_rank("sensor_0"), 0x9f0f_ad06_6528_e534);
+
+        let nodes: Vec<String> = (0..10).map(|i| format!("sensor_{}", i)).collect();
+        assert_eq!(
+            rollout_cohort(nodes.clone(), 30),
+            vec!["sensor_8", "sensor_9", "sensor_4"]
+        );
+        assert_eq!(
+            rollout_cohort(nodes.clone(), 50),
+            vec!["sensor_8", "sensor_9", "sensor_4", "sensor_5", "sensor_6"]
+        );
+        assert!(rollout_cohort(nodes.clone(), 0).is_empty());
+        assert_eq!(rollout_cohort(nodes, 100).len(), 10);
+    }
+
     #[test]
     fn test_node_state_new() {
         let node_data = NodeData {
             node_id: "test_node".to_string(),
             node_type: "test_type".to_string(),
-            status: "online".to_string(),
+            status: NodeStatus::Online,
             timestamp: 1234567890,
             metadata: None,
+            labels: Default::default(),
         };
 
         let node_state = NodeState::new(node_data.clone());
 
         assert_eq!(node_state.last_value, node_data);
         assert!(node_state.last_update <= std::time::SystemTime::now());
+        assert!(node_state.offline_reason.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_retry_is_jittered_and_bounded() {
+        let retry_config = RetryConfig {
+            initial_interval: Duration::from_millis(20),
+            max_interval: Duration::from_millis(50),
+            max_elapsed_time: Duration::from_millis(300),
+            ..Default::default()
+        };
+
+        // Two schedules built from the same config should not retry in lockstep
+        let mut first = retry_config.backoff();
+        let mut second = retry_config.backoff();
+        let first_delays: Vec<_> = (0..5).map(|_| first.next_backoff().unwrap()).collect();
+        let second_delays: Vec<_> = (0..5).map(|_| second.next_backoff().unwrap()).collect();
+        assert_ne!(first_delays, second_delays);
+        assert!(first_delays
+            .iter()
+            .all(|delay| *delay <= Duration::from_millis(75)));
+
+        // A flaky put that never recovers gives up after the max elapsed time
+        let attempts = std::sync::atomic::AtomicUsize::new(0);
+        let start = std::time::Instant::now();
+        let result: Result<(), String> = retry_config
+            .retry("put", || async {
+                attempts.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                Err("flaky put".to_string())
+            })
+            .await;
+
+        assert!(result.is_err());
+        assert!(attempts.load(std::sync::atomic::Ordering::SeqCst) > 1);
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_retry_if_fails_fast_on_permanent_errors() {
+        let attempts = std::sync::atomic::AtomicUsize::new(0);
+        let result: Result<(), FabricError> = RetryConfig::default()
+            .retry_if(
+                "publish",
+                || async {
+                    attempts.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                    Err(FabricError::InvalidConfig("bad config".to_string()))
+                },
+                FabricError::is_retryable,
+            )
+            .await;
+
+        assert!(matches!(result, Err(FabricError::InvalidConfig(_))));
+        assert_eq!(attempts.load(std::sync::atomic::Ordering::SeqCst), 1);
     }
 }
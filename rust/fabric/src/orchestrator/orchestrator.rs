@@ -1,21 +1,49 @@
-use super::NodeState;
+use super::{
+    rollout_cohort, Metrics, MetricsSnapshot, NetworkSnapshot, NodeState, OrchestratorConfig,
+    OFFLINE_REASON_EXPLICIT, OFFLINE_REASON_HEARTBEAT_TIMEOUT, OFFLINE_REASON_LIVELINESS_LOST,
+};
 use crate::error::{FabricError, Result};
-use crate::node::interface::{NodeConfig, NodeData};
-use backoff::{backoff::Backoff, ExponentialBackoff};
+use crate::keys;
+use crate::node::interface::{
+    BroadcastMessage, Heartbeat, NodeConfig, NodeData, NodeStatus, PatchOp,
+};
+use crate::node::persistence;
+use crate::payload::decode_value;
+use crate::publisher::PublisherOptions;
+use crate::shutdown::Component;
+use crate::subscriber::{exceeds_max_payload, Deduplicator, SubscriberOptions, ZenohSubscriber};
+use async_trait::async_trait;
+use futures::future::join_all;
 use log::{debug, error, info, warn};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::SystemTime;
 use tokio::sync::mpsc;
-use tokio::sync::{Mutex, RwLock};
+use tokio::sync::{Mutex, RwLock, Semaphore};
 use tokio::time::interval;
-use tokio::time::{sleep, Duration};
+use tokio::time::{sleep, Duration, Instant};
 use tokio_util::sync::CancellationToken;
 use zenoh::prelude::r#async::*;
 
 // Add this near the top of the file, after the imports
 type NodeDataCallback = Arc<Mutex<dyn Fn(NodeData) + Send + Sync>>;
+// Zenoh only allows `*` as a whole chunk; partial-chunk wildcards are spelled `$*`
+fn node_id_pattern(pattern: &str) -> String {
+    pattern
+        .split('/')
+        .map(|chunk| match chunk {
+            "*" | "**" => chunk.to_string(),
+            _ => chunk.replace("$*", "*").replace('*', "$*"),
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+// Receives the previous value (None for a first-seen node) and the new one
+type TransitionCallback = Arc<Mutex<dyn Fn(Option<NodeData>, NodeData) + Send + Sync>>;
 
 pub struct Publisher {
     topic: String,
@@ -24,8 +52,34 @@ pub struct Publisher {
 
 pub struct Subscriber {
     topic: String,
-    callback: Arc<Mutex<dyn Fn(Sample) + Send + Sync>>,
-    zenoh_subscriber: zenoh::subscriber::Subscriber<'static, ()>,
+    // Feeds this subscriber's dispatch task, which runs its callback in order
+    dispatch_tx: mpsc::Sender<Sample>,
+    zenoh_subscriber: ZenohSubscriber,
+    dedup: Option<std::sync::Mutex<Deduplicator>>,
+}
+
+impl Subscriber {
+    fn accepts(&self, sample: &Sample) -> bool {
+        if !self
+            .zenoh_subscriber
+            .key_expr()
+            .intersects(sample.key_expr.as_keyexpr())
+        {
+            return false;
+        }
+        if let Some(dedup) = &self.dedup {
+            if !dedup.lock().unwrap().observe(sample) {
+                debug!("Dropping duplicate sample on {}", self.topic);
+                return false;
+            }
+        }
+        true
+    }
+}
+
+struct QueuedCommand {
+    message: BroadcastMessage,
+    queued_at: Instant,
 }
 
 #[derive(Clone)]
@@ -34,25 +88,84 @@ pub struct Orchestrator {
     pub session: Arc<Session>,
     pub nodes: Arc<Mutex<HashMap<String, NodeState>>>,
     callbacks: Arc<Mutex<HashMap<String, NodeDataCallback>>>,
+    transition_callbacks: Arc<Mutex<HashMap<String, TransitionCallback>>>,
+    pattern_callbacks: Arc<Mutex<Vec<(OwnedKeyExpr, NodeDataCallback)>>>,
     pub subscribers: Arc<RwLock<HashMap<String, Subscriber>>>,
     pub publishers: Arc<RwLock<HashMap<String, Publisher>>>,
     status_subscriber: Arc<Mutex<Option<zenoh::subscriber::Subscriber<'static, ()>>>>,
+    config_queryable: Arc<Mutex<Option<zenoh::queryable::Queryable<'static, ()>>>>,
+    published_configs: Arc<Mutex<HashMap<String, NodeConfig>>>,
     subscriber_tx: mpsc::Sender<Sample>,
+    // Status samples in arrival order, applied one at a time
+    status_tx: mpsc::Sender<Sample>,
+    config: OrchestratorConfig,
+    metrics: Arc<Metrics>,
+    paused: Arc<AtomicBool>,
+    command_queues: Arc<Mutex<HashMap<String, VecDeque<QueuedCommand>>>>,
+    leader: Arc<AtomicBool>,
+    leader_candidates: Arc<std::sync::Mutex<BTreeSet<String>>>,
+    // When the last node status was accepted, for idle_timeout
+    last_status_at: Arc<std::sync::Mutex<Instant>>,
+    publish_permits: Option<Arc<Semaphore>>,
+    // Held across each snapshot write so snapshots land on disk in the order taken
+    snapshot_lock: Arc<Mutex<()>>,
 }
 
 impl Orchestrator {
     pub async fn new(id: String, session: Arc<Session>) -> Result<Arc<Self>> {
+        Self::with_config(id, session, OrchestratorConfig::default()).await
+    }
+
+    pub async fn with_timeouts(
+        id: String,
+        session: Arc<Session>,
+        offline_timeout: Duration,
+        health_check_interval: Duration,
+    ) -> Result<Arc<Self>> {
+        let config = OrchestratorConfig {
+            offline_timeout,
+            health_check_interval,
+            ..Default::default()
+        };
+        Self::with_config(id, session, config).await
+    }
+
+    pub async fn with_config(
+        id: String,
+        session: Arc<Session>,
+        config: OrchestratorConfig,
+    ) -> Result<Arc<Self>> {
         info!("Creating new orchestrator: {}", id);
-        let (subscriber_tx, subscriber_rx) = mpsc::channel(100);
+        let (subscriber_tx, subscriber_rx) = mpsc::channel(config.ingest_capacity);
+        let (status_tx, status_rx) = mpsc::channel(config.ingest_capacity);
+        // Without an election every orchestrator acts as leader
+        let leader = Arc::new(AtomicBool::new(!config.leader_election));
+        let publish_permits = config
+            .max_concurrent_publishes
+            .map(|permits| Arc::new(Semaphore::new(permits)));
         let orchestrator = Self {
             id,
             session,
             nodes: Arc::new(Mutex::new(HashMap::new())),
             callbacks: Arc::new(Mutex::new(HashMap::new())),
+            transition_callbacks: Arc::new(Mutex::new(HashMap::new())),
+            pattern_callbacks: Arc::new(Mutex::new(Vec::new())),
             subscribers: Arc::new(RwLock::new(HashMap::new())),
             publishers: Arc::new(RwLock::new(HashMap::new())),
             status_subscriber: Arc::new(Mutex::new(None)),
+            config_queryable: Arc::new(Mutex::new(None)),
+            published_configs: Arc::new(Mutex::new(HashMap::new())),
             subscriber_tx,
+            status_tx,
+            config,
+            metrics: Arc::new(Metrics::default()),
+            paused: Arc::new(AtomicBool::new(false)),
+            command_queues: Arc::new(Mutex::new(HashMap::new())),
+            leader,
+            leader_candidates: Arc::new(std::sync::Mutex::new(BTreeSet::new())),
+            last_status_at: Arc::new(std::sync::Mutex::new(Instant::now())),
+            publish_permits,
+            snapshot_lock: Arc::new(Mutex::new(())),
         };
 
         // Spawn a task to handle subscriber samples
@@ -63,41 +176,111 @@ impl Orchestrator {
                 .await;
         });
 
+        // Spawn a task to apply node status samples
+        let orchestrator_clone = orchestrator.clone();
+        tokio::spawn(async move {
+            orchestrator_clone.handle_status_samples(status_rx).await;
+        });
+
         Ok(Arc::new(orchestrator))
     }
 
     pub async fn run(&self, cancel: CancellationToken) -> Result<()> {
         info!("Starting orchestrator: {}", self.id);
+        // A child token lets an idle orchestrator stop itself without cancelling the caller's
+        let cancel = cancel.child_token();
+        *self.last_status_at.lock().unwrap() = Instant::now();
 
         // Subscribe to all node status topics
         self.subscribe_to_node_statuses().await?;
 
+        // Dropped on shutdown, handing leadership to the next candidate
+        let _leader_election = if self.config.leader_election {
+            Some(self.join_leader_election().await?)
+        } else {
+            None
+        };
+
+        // Serve last published configs to nodes that fetch on demand
+        self.serve_node_configs().await?;
+        let topology_queryable = self.serve_network_topology().await?;
+
         // Start a task to check for offline nodes
         let offline_check_task = {
             let self_clone = self.clone();
             let cancel_clone = cancel.clone();
+            let grace_deadline = Instant::now() + self.config.offline_grace_period;
             tokio::spawn(async move {
-                let mut interval = interval(Duration::from_secs(1));
+                let mut interval = interval(self_clone.config.health_check_interval);
                 loop {
                     tokio::select! {
                         _ = cancel_clone.cancelled() => break,
-                        _ = interval.tick() => self_clone.check_offline_nodes().await,
+                        _ = interval.tick() => {
+                            // Give nodes time to report before judging them offline
+                            if Instant::now() >= grace_deadline {
+                                self_clone.check_offline_nodes().await;
+                            }
+                        }
                     }
                 }
             })
         };
 
-        // Wait for cancellation
-        cancel.cancelled().await;
+        // Optionally keep nodes converged on their desired configs
+        let reconcile_task = self.config.reconcile_interval.map(|period| {
+            let self_clone = self.clone();
+            let cancel_clone = cancel.clone();
+            tokio::spawn(async move {
+                let mut interval = interval(period);
+                loop {
+                    tokio::select! {
+                        _ = cancel_clone.cancelled() => break,
+                        _ = interval.tick() => self_clone.reconcile_configs().await,
+                    }
+                }
+            })
+        });
+
+        // Wait for cancellation, or for nodes to go quiet for idle_timeout
+        match self.config.idle_timeout {
+            Some(idle_timeout) => loop {
+                let deadline = *self.last_status_at.lock().unwrap() + idle_timeout;
+                tokio::select! {
+                    _ = cancel.cancelled() => break,
+                    _ = tokio::time::sleep_until(deadline) => {
+                        if self.last_status_at.lock().unwrap().elapsed() >= idle_timeout {
+                            info!(
+                                "Orchestrator {} received no status updates for {:?}, stopping",
+                                self.id, idle_timeout
+                            );
+                            cancel.cancel();
+                            break;
+                        }
+                    }
+                }
+            },
+            None => cancel.cancelled().await,
+        }
         info!("Orchestrator {} shutting down", self.id);
 
         // Unsubscribe from node status topics
         self.unsubscribe_from_node_statuses().await?;
+        self.unserve_node_configs().await?;
+        topology_queryable
+            .undeclare()
+            .res()
+            .await
+            .map_err(FabricError::ZenohError)?;
 
         // Wait for the offline check task to complete
         offline_check_task
             .await
             .map_err(|e| FabricError::Other(format!("Offline check task error: {}", e)))?;
+        if let Some(reconcile_task) = reconcile_task {
+            reconcile_task
+                .await
+                .map_err(|e| FabricError::Other(format!("Reconcile task error: {}", e)))?;
+        }
 
         info!("Orchestrator {} shutdown complete", self.id);
 
@@ -108,12 +291,32 @@ impl Orchestrator {
         let orchestrator = self.clone();
         let subscriber = self
             .session
-            .declare_subscriber("fabric/*/status")
+            .declare_subscriber(keys::status_key(self.namespace(), "*"))
             .callback(move |sample| {
-                let orchestrator_clone = orchestrator.clone();
-                tokio::spawn(async move {
-                    orchestrator_clone.update_node_health(sample).await;
-                });
+                if orchestrator.oversized(&sample) {
+                    return;
+                }
+                // Forward inline: a task per sample could apply one node's updates out
+                // of order, and a flood would pile up tasks without bound
+                match orchestrator.status_tx.try_send(sample) {
+                    Ok(()) => {}
+                    Err(mpsc::error::TrySendError::Full(sample)) => {
+                        let dropped = orchestrator
+                            .metrics
+                            .ingest_dropped
+                            .fetch_add(1, Ordering::Relaxed)
+                            + 1;
+                        if dropped % 100 == 1 {
+                            warn!(
+                                "Status queue full, dropped sample on {} ({} dropped so far)",
+                                sample.key_expr, dropped
+                            );
+                        }
+                    }
+                    Err(mpsc::error::TrySendError::Closed(_)) => {
+                        error!("Failed to send status sample to handler: channel closed");
+                    }
+                }
             })
             .res()
             .await
@@ -138,40 +341,195 @@ impl Orchestrator {
         Ok(())
     }
 
+    pub async fn serve_node_configs(&self) -> Result<()> {
+        let orchestrator = self.clone();
+        let queryable = self
+            .session
+            .declare_queryable(self.node_key("*", "config"))
+            .callback(move |query| {
+                let orchestrator_clone = orchestrator.clone();
+                tokio::spawn(async move {
+                    orchestrator_clone.reply_node_config(query).await;
+                });
+            })
+            .res()
+            .await
+            .map_err(FabricError::ZenohError)?;
+
+        let mut config_queryable = self.config_queryable.lock().await;
+        *config_queryable = Some(queryable);
+
+        Ok(())
+    }
+
+    pub async fn unserve_node_configs(&self) -> Result<()> {
+        let mut config_queryable = self.config_queryable.lock().await;
+        if let Some(queryable) = config_queryable.take() {
+            queryable
+                .undeclare()
+                .res()
+                .await
+                .map_err(FabricError::ZenohError)?;
+        }
+        Ok(())
+    }
+
+    pub async fn network_topology(&self) -> NetworkSnapshot {
+        let info = self.session.info();
+        let mut routers: Vec<String> = info
+            .routers_zid()
+            .res()
+            .await
+            .map(|zid| zid.to_string())
+            .collect();
+        let mut peers: Vec<String> = info
+            .peers_zid()
+            .res()
+            .await
+            .map(|zid| zid.to_string())
+            .collect();
+        routers.sort();
+        peers.sort();
+        NetworkSnapshot {
+            zid: info.zid().res().await.to_string(),
+            routers,
+            peers,
+        }
+    }
+
+    // Answers <namespace>/orchestrator/<id>/topology with a JSON NetworkSnapshot
+    async fn serve_network_topology(&self) -> Result<zenoh::queryable::Queryable<'static, ()>> {
+        let key = keys::orchestrator_key(self.namespace(), &self.id, "topology");
+        let key_expr = KeyExpr::try_from(key.clone()).map_err(FabricError::ZenohError)?;
+        let orchestrator = self.clone();
+        self.session
+            .declare_queryable(key)
+            .callback(move |query| {
+                let orchestrator = orchestrator.clone();
+                let key_expr = key_expr.clone();
+                tokio::spawn(async move {
+                    let snapshot = orchestrator.network_topology().await;
+                    let payload = match serde_json::to_vec(&snapshot) {
+                        Ok(payload) => payload,
+                        Err(e) => {
+                            warn!("Failed to serialize network topology: {}", e);
+                            return;
+                        }
+                    };
+                    let value = zenoh::value::Value::from(payload).encoding(Encoding::APP_JSON);
+                    if let Err(e) = query.reply(Ok(Sample::new(key_expr, value))).res().await {
+                        warn!("Failed to reply with network topology: {}", e);
+                    }
+                });
+            })
+            .res()
+            .await
+            .map_err(FabricError::ZenohError)
+    }
+
+    async fn reply_node_config(&self, query: zenoh::queryable::Query) {
+        let published_configs = self.published_configs.lock().await;
+        for (node_id, config) in published_configs.iter() {
+            let key = self.node_key(node_id, "config");
+            let Ok(key_expr) = KeyExpr::try_from(key) else {
+                continue;
+            };
+            if !query.key_expr().intersects(&key_expr) {
+                continue;
+            }
+            match self.encode_payload(config) {
+                Ok(payload) => {
+                    if let Err(e) = query.reply(Ok(Sample::new(key_expr, payload))).res().await {
+                        warn!("Failed to reply with config for node {}: {}", node_id, e);
+                    }
+                }
+                Err(e) => {
+                    warn!("Failed to serialize config for node {}: {}", node_id, e);
+                }
+            }
+        }
+    }
+
     async fn update_node_health(&self, sample: Sample) {
+        if self.drop_if_paused() {
+            return;
+        }
+
         let key_expr = sample.key_expr.as_str();
-        let node_id = key_expr.split('/').nth(1).unwrap_or("unknown");
+        let node_id =
+            keys::node_id_from_status_key(self.namespace(), key_expr).unwrap_or("unknown");
         info!("Received health update for node: {}", node_id);
 
-        // Convert ZBuf to a contiguous slice of bytes
-        let payload_bytes = sample.value.payload.contiguous();
+        let mut reconnected = false;
 
         // Deserialize the payload into a serde_json::Value
-        match serde_json::from_slice::<serde_json::Value>(&payload_bytes) {
+        match decode_value::<serde_json::Value>(&sample.value) {
             Ok(json_value) => {
                 debug!("Deserialized JSON: {:?}", json_value);
 
                 let mut nodes = self.nodes.lock().await;
-                let node_state = nodes
-                    .entry(node_id.to_string())
-                    .or_insert_with(|| NodeState {
-                        last_value: NodeData::from_json(&json_value.to_string()).unwrap(),
-                        last_update: std::time::SystemTime::now(),
+                // Lightweight heartbeats update the last full report
+                let node_data = NodeData::from_json(&json_value.to_string())
+                    .ok()
+                    .or_else(|| {
+                        let heartbeat = serde_json::from_value::<Heartbeat>(json_value).ok()?;
+                        Some(heartbeat.apply_to(nodes.get(node_id).map(|state| &state.last_value)))
                     });
+                if let Some(mut node_data) = node_data {
+                    if !self.is_allowed_type(&node_data.node_type) {
+                        warn!(
+                            "Ignoring node {} of disallowed type {}",
+                            node_id, node_data.node_type
+                        );
+                        self.metrics
+                            .disallowed_type_dropped
+                            .fetch_add(1, Ordering::Relaxed);
+                        return;
+                    }
+                    self.metrics
+                        .status_updates_total
+                        .fetch_add(1, Ordering::Relaxed);
+                    *self.last_status_at.lock().unwrap() = Instant::now();
+                    let first_seen = !nodes.contains_key(node_id);
+                    let node_state = nodes
+                        .entry(node_id.to_string())
+                        .or_insert_with(|| NodeState::new(node_data.clone()));
+                    let was_offline = node_state.last_value.status == NodeStatus::Offline;
+                    if self.config.merge_metadata {
+                        let patch = node_data.metadata.take();
+                        node_data.metadata = node_state.last_value.metadata.clone();
+                        if let Some(patch) = patch {
+                            node_data.merge_metadata(&patch);
+                        }
+                    }
+                    // Keepalives repeat the last report with a new timestamp
+                    let previous = (!first_seen).then(|| node_state.last_value.clone());
+                    let keepalive = !first_seen
+                        && !node_state.has_pending_status()
+                        && NodeData {
+                            timestamp: node_state.last_value.timestamp,
+                            ..node_data.clone()
+                        } == node_state.last_value;
+                    node_state.update(node_data);
+                    self.measure_clock_skew(node_id, node_state);
+                    node_state.offline_reason =
+                        if node_state.last_value.status == NodeStatus::Offline {
+                            Some(OFFLINE_REASON_EXPLICIT.to_string())
+                        } else {
+                            None
+                        };
 
-                if let Ok(node_data) = NodeData::from_json(&json_value.to_string()) {
-                    node_state.last_value = node_data;
-                    node_state.last_update = std::time::SystemTime::now();
-
-                    if node_state.last_value.status != "online" {
+                    if node_state.last_value.status != NodeStatus::Online {
                         warn!("Node {} is {}", node_id, node_state.last_value.status);
+                    } else if was_offline {
+                        info!("Node {} reconnected", node_id);
+                        reconnected = true;
                     }
 
                     // Trigger callbacks
-                    let callbacks = self.callbacks.lock().await;
-                    if let Some(callback) = callbacks.get(node_id) {
-                        let callback = callback.lock().await;
-                        callback(node_state.last_value.clone());
+                    if !keepalive && self.debounce_status(node_state) {
+                        self.fire_callbacks(node_id, previous, node_state.callback_data())
+                            .await;
                     }
                 } else {
                     warn!("Failed to parse NodeData from JSON for node {}", node_id);
@@ -181,104 +539,508 @@ impl Orchestrator {
                 warn!("Failed to parse JSON payload for node {}: {}", node_id, e);
             }
         }
+
+        if reconnected && self.config.reconcile_on_reconnect {
+            self.republish_node_config(node_id).await;
+        }
+
+        if self.is_node_online(node_id).await {
+            self.flush_commands(node_id).await;
+        }
+    }
+
+    // Every orchestrator announces a liveliness token under <namespace>/leader. Tokens are
+    // not exclusive, so the live candidate with the lowest id is the leader; when its
+    // token drops the next one takes over.
+    async fn join_leader_election(
+        &self,
+    ) -> Result<(
+        zenoh::liveliness::LivelinessToken<'static>,
+        zenoh::subscriber::Subscriber<'static, ()>,
+    )> {
+        self.leader_candidates
+            .lock()
+            .unwrap()
+            .insert(self.id.clone());
+
+        let orchestrator = self.clone();
+        let subscriber = self
+            .session
+            .liveliness()
+            .declare_subscriber(format!("{}/*", keys::leader_key_prefix(self.namespace())))
+            .callback(move |sample| {
+                let Some(candidate) = sample.key_expr.as_str().rsplit('/').next() else {
+                    return;
+                };
+                let mut candidates = orchestrator.leader_candidates.lock().unwrap();
+                match sample.kind {
+                    SampleKind::Put => candidates.insert(candidate.to_string()),
+                    SampleKind::Delete => candidates.remove(candidate),
+                };
+                orchestrator.elect_leader(&candidates);
+            })
+            .res()
+            .await
+            .map_err(FabricError::ZenohError)?;
+
+        let token = self
+            .session
+            .liveliness()
+            .declare_token(format!(
+                "{}/{}",
+                keys::leader_key_prefix(self.namespace()),
+                self.id
+            ))
+            .res()
+            .await
+            .map_err(FabricError::ZenohError)?;
+
+        // Pick up candidates that were alive before we subscribed
+        let replies = self
+            .session
+            .liveliness()
+            .get(format!("{}/*", keys::leader_key_prefix(self.namespace())))
+            .res()
+            .await
+            .map_err(FabricError::ZenohError)?;
+        while let Ok(reply) = replies.recv_async().await {
+            if let Ok(sample) = reply.sample {
+                if let Some(candidate) = sample.key_expr.as_str().rsplit('/').next() {
+                    self.leader_candidates
+                        .lock()
+                        .unwrap()
+                        .insert(candidate.to_string());
+                }
+            }
+        }
+        self.elect_leader(&self.leader_candidates.lock().unwrap());
+
+        Ok((token, subscriber))
+    }
+
+    fn elect_leader(&self, candidates: &BTreeSet<String>) {
+        let is_leader = candidates.first() == Some(&self.id);
+        if self.leader.swap(is_leader, Ordering::SeqCst) != is_leader {
+            info!(
+                "Orchestrator {} is now {}",
+                self.id,
+                if is_leader { "leader" } else { "follower" }
+            );
+        }
+    }
+
+    pub fn is_leader(&self) -> bool {
+        self.leader.load(Ordering::SeqCst)
+    }
+
+    fn ensure_leader(&self) -> Result<()> {
+        if self.is_leader() {
+            Ok(())
+        } else {
+            Err(FabricError::NotLeader(self.id.clone()))
+        }
+    }
+
+    async fn republish_node_config(&self, node_id: &str) {
+        if !self.is_leader() {
+            return;
+        }
+        let config = self.published_configs.lock().await.get(node_id).cloned();
+        if let Some(config) = config {
+            info!("Re-publishing config to reconnected node {}", node_id);
+            if let Err(e) = self.publish_node_config(node_id, &config).await {
+                error!("Failed to re-publish config to node {}: {:?}", node_id, e);
+            }
+        }
+    }
+
+    // Configs and patches go out in the configured encoding, declared on the sample
+    fn encode_payload<T: serde::Serialize>(&self, value: &T) -> Result<zenoh::value::Value> {
+        let payload = self.config.encoding.encode(value)?;
+        Ok(zenoh::value::Value::from(payload).encoding(self.config.encoding.zenoh_encoding()))
     }
 
     pub async fn publish_node_config(&self, node_id: &str, config: &NodeConfig) -> Result<()> {
-        let key = format!("node/{}/config", node_id);
-        let config_json = serde_json::to_string(config)?;
-        let mut backoff = ExponentialBackoff::default();
+        self.ensure_leader()?;
+        let key = self.node_key(node_id, "config");
+        let payload = self.encode_payload(config)?;
+        self.metrics
+            .published_payload_bytes
+            .record(payload.payload.len() as u64);
+
+        // Held through retries, so a batch to thousands of nodes can't flood the session
+        let _permit = match &self.publish_permits {
+            Some(permits) => Some(
+                permits
+                    .acquire()
+                    .await
+                    .map_err(|e| FabricError::Other(e.to_string()))?,
+            ),
+            None => None,
+        };
+        let _in_flight = self.metrics.begin_config_publish();
+        self.config
+            .publish_retry
+            .retry_if(
+                "publish config",
+                || async {
+                    self.session
+                        .put(&key, payload.clone())
+                        .res()
+                        .await
+                        .map_err(FabricError::ZenohError)
+                },
+                FabricError::is_retryable,
+            )
+            .await
+            .map_err(|err| FabricError::PublishError(err.to_string()))?;
+        self.metrics
+            .configs_published_total
+            .fetch_add(1, Ordering::Relaxed);
+
+        info!(
+            "Orchestrator {} successfully published config to node {}: {:?}",
+            self.id, node_id, config
+        );
+        self.published_configs
+            .lock()
+            .await
+            .insert(node_id.to_string(), config.clone());
+        Ok(())
+    }
+
+    // Publish each config to its `node_id` concurrently, returning per-node results in
+    // the order given so partial failures are visible
+    pub async fn publish_node_configs(
+        &self,
+        configs: &[NodeConfig],
+    ) -> Result<Vec<(String, Result<()>)>> {
+        self.ensure_leader()?;
+        let publishes = configs.iter().map(|config| async move {
+            let result = self.publish_node_config(&config.node_id, config).await;
+            (config.node_id.clone(), result)
+        });
+        Ok(join_all(publishes).await)
+    }
+
+    // Push `config` to `percent` of the online nodes of `node_type`. Nodes are ranked by
+    // a hash of their id, so repeated rollouts pick the same nodes first and ramping up
+    // only adds to the set. Returns the ids the config went to.
+    pub async fn rollout(
+        &self,
+        node_type: &str,
+        config: &Value,
+        percent: u8,
+    ) -> Result<Vec<String>> {
+        if percent > 100 {
+            return Err(FabricError::InvalidConfig(format!(
+                "rollout percent {} is over 100",
+                percent
+            )));
+        }
+        let candidates = self
+            .find_nodes(|node_data| {
+                node_data.node_type == node_type && node_data.status == NodeStatus::Online
+            })
+            .await;
+        let candidates = rollout_cohort(candidates, percent);
+
+        self.publish_config_to(&candidates, config).await?;
+        info!(
+            "Rolled out config to {}% of {} nodes: {:?}",
+            percent, node_type, candidates
+        );
+        Ok(candidates)
+    }
+
+    // Undo a rollout by re-publishing the previous config to the nodes it reached
+    pub async fn rollback(&self, node_ids: &[String], old_config: &Value) -> Result<()> {
+        self.publish_config_to(node_ids, old_config).await?;
+        info!("Rolled back config on nodes {:?}", node_ids);
+        Ok(())
+    }
 
+    async fn publish_config_to(&self, node_ids: &[String], config: &Value) -> Result<()> {
+        let configs: Vec<NodeConfig> = node_ids
+            .iter()
+            .map(|node_id| NodeConfig {
+                node_id: node_id.clone(),
+                config: config.clone(),
+            })
+            .collect();
+        for (node_id, result) in self.publish_node_configs(&configs).await? {
+            result.map_err(|e| {
+                FabricError::PublishError(format!("config for node {}: {}", node_id, e))
+            })?;
+        }
+        Ok(())
+    }
+
+    // Publish a JSON merge patch applied by every node listing `group` in its config
+    pub async fn publish_group_config(&self, group: &str, patch: Value) -> Result<()> {
+        self.ensure_leader()?;
+        let key = keys::group_config_key(self.namespace(), group);
+        let payload = self.encode_payload(&patch)?;
+
+        self.config
+            .publish_retry
+            .retry_if(
+                "publish group config",
+                || async {
+                    self.session
+                        .put(&key, payload.clone())
+                        .res()
+                        .await
+                        .map_err(FabricError::ZenohError)
+                },
+                FabricError::is_retryable,
+            )
+            .await
+            .map_err(|err| FabricError::PublishError(err.to_string()))?;
+
+        info!(
+            "Orchestrator {} published config to group {}: {:?}",
+            self.id, group, patch
+        );
+        Ok(())
+    }
+
+    // Ask a running node for the config it has actually applied
+    pub async fn query_active_config(
+        &self,
+        node_id: &str,
+        timeout: Duration,
+    ) -> Result<Option<NodeConfig>> {
+        let key = self.node_key(node_id, "config/active");
+        let replies = self
+            .session
+            .get(&key)
+            .timeout(timeout)
+            .res()
+            .await
+            .map_err(FabricError::ZenohError)?;
+
+        while let Ok(reply) = replies.recv_async().await {
+            if let Ok(sample) = reply.sample {
+                if self.oversized(&sample) {
+                    continue;
+                }
+                let config: NodeConfig = decode_value(&sample.value)?;
+                return Ok(Some(config));
+            }
+        }
+        Ok(None)
+    }
+
+    // Publish configs, then wait until each node reports them applied. Returns the ids
+    // of nodes that did not confirm before the timeout.
+    pub async fn apply_and_verify(
+        &self,
+        configs: Vec<(String, NodeConfig)>,
+        timeout: Duration,
+    ) -> Result<Vec<String>> {
+        for (node_id, config) in &configs {
+            self.publish_node_config(node_id, config).await?;
+        }
+
+        let deadline = Instant::now() + timeout;
+        let mut pending = configs;
         loop {
-            match self.session.put(&key, config_json.clone()).res().await {
-                Ok(_) => {
+            let mut unconfirmed = Vec::new();
+            for (node_id, config) in pending {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                let query_timeout =
+                    remaining.clamp(Duration::from_millis(100), Duration::from_secs(1));
+                match self.query_active_config(&node_id, query_timeout).await {
+                    Ok(Some(active)) if active == config => {
+                        debug!("Node {} confirmed config", node_id);
+                    }
+                    _ => unconfirmed.push((node_id, config)),
+                }
+            }
+            pending = unconfirmed;
+            if pending.is_empty() || Instant::now() >= deadline {
+                break;
+            }
+            sleep(Duration::from_millis(100)).await;
+        }
+
+        Ok(pending.into_iter().map(|(node_id, _)| node_id).collect())
+    }
+
+    // Compare each desired config against what the node reports and re-publish on drift
+    pub async fn reconcile_configs(&self) {
+        if !self.is_leader() {
+            return;
+        }
+        for desired in &self.config.nodes {
+            match self
+                .query_active_config(&desired.node_id, self.config.health_query_timeout)
+                .await
+            {
+                Ok(Some(active)) if active == *desired => {}
+                Ok(Some(_)) => {
                     info!(
-                        "Orchestrator {} successfully published config to node {}: {:?}",
-                        self.id, node_id, config
+                        "Config drift detected on node {}, re-publishing",
+                        desired.node_id
                     );
-                    return Ok(());
-                }
-                Err(err) => {
-                    if let Some(duration) = backoff.next_backoff() {
+                    if let Err(e) = self.publish_node_config(&desired.node_id, desired).await {
                         warn!(
-                            "Failed to publish config, retrying in {:?}: {}",
-                            duration, err
+                            "Failed to reconcile config for node {}: {:?}",
+                            desired.node_id, e
                         );
-                        sleep(duration).await;
-                    } else {
-                        return Err(FabricError::PublishError(err.to_string()));
                     }
                 }
+                Ok(None) => debug!("Node {} did not report its config", desired.node_id),
+                Err(e) => warn!(
+                    "Failed to query config of node {}: {:?}",
+                    desired.node_id, e
+                ),
             }
         }
     }
 
     pub async fn update_node_state(&self, node_data: NodeData) {
         let mut nodes = self.nodes.lock().await;
-        nodes.insert(
-            node_data.node_id.clone(),
-            NodeState {
-                last_value: node_data.clone(),
-                last_update: SystemTime::now(),
-            },
-        );
+        let node_id = node_data.node_id.clone();
+        let previous = nodes.get(&node_id).map(|state| state.last_value.clone());
+        let node_state = nodes
+            .entry(node_id.clone())
+            .or_insert_with(|| NodeState::new(node_data.clone()));
+        node_state.update(node_data);
+        node_state.offline_reason = if node_state.last_value.status == NodeStatus::Offline {
+            Some(OFFLINE_REASON_EXPLICIT.to_string())
+        } else {
+            None
+        };
 
+        if self.debounce_status(node_state) {
+            self.fire_callbacks(&node_id, previous, node_state.callback_data())
+                .await;
+        }
+    }
+
+    // Statuses carry the node's own epoch-second clock; compare it with ours on receipt
+    fn measure_clock_skew(&self, node_id: &str, node_state: &mut NodeState) {
+        let reported = node_state.last_value.timestamp;
+        if reported == 0 {
+            return;
+        }
+        let Ok(now) = SystemTime::now().duration_since(std::time::UNIX_EPOCH) else {
+            return;
+        };
+        let skew = Duration::from_secs(now.as_secs().abs_diff(reported));
+        let skewed = skew > self.config.clock_skew_threshold;
+        if skewed && !node_state.clock_skewed {
+            warn!(
+                "Node {} clock is off by {:?}, exceeding {:?}",
+                node_id, skew, self.config.clock_skew_threshold
+            );
+        }
+        node_state.clock_skew = Some(skew);
+        node_state.clock_skewed = skewed;
+    }
+
+    pub async fn get_clock_skew(&self, node_id: &str) -> Option<Duration> {
+        self.nodes.lock().await.get(node_id)?.clock_skew
+    }
+
+    // Decide whether the node's current status should reach callbacks. Updates that
+    // keep the reported status always pass; a changed status only passes once it has
+    // held for the configured debounce.
+    fn debounce_status(&self, node_state: &mut NodeState) -> bool {
+        let status = node_state.last_value.status;
+        let settled = self.config.status_debounce.is_zero()
+            || node_state.reported_status.is_none()
+            || node_state.reported_status == Some(status)
+            || SystemTime::now()
+                .duration_since(node_state.status_since)
+                .is_ok_and(|held| held >= self.config.status_debounce);
+        if settled {
+            node_state.reported_status = Some(status);
+        }
+        settled
+    }
+
+    async fn fire_callbacks(&self, node_id: &str, previous: Option<NodeData>, node_data: NodeData) {
+        if let Some(callback) = self.transition_callbacks.lock().await.get(node_id) {
+            let callback = callback.lock().await;
+            callback(previous, node_data.clone());
+        }
         let callbacks = self.callbacks.lock().await;
-        if let Some(callback) = callbacks.get(&node_data.node_id) {
+        if let Some(callback) = callbacks.get(node_id) {
             let callback = callback.lock().await;
-            callback(node_data);
+            callback(node_data.clone());
+        }
+        drop(callbacks);
+        let Ok(node_key) = keyexpr::new(node_id) else {
+            return;
+        };
+        for (pattern, callback) in self.pattern_callbacks.lock().await.iter() {
+            if pattern.intersects(node_key) {
+                let callback = callback.lock().await;
+                callback(node_data.clone());
+            }
         }
     }
 
+    // Ask a node for its current state instead of waiting for its next heartbeat; a node
+    // that isn't tracked yet is added, while one that is keeps its live state
+    pub async fn query_node_state(&self, node_id: &str) -> Result<Option<NodeData>> {
+        let query_timeout = self.config.health_query_timeout;
+        let replies = self
+            .session
+            .get(keys::state_key(self.namespace(), node_id))
+            .timeout(query_timeout)
+            .res()
+            .await
+            .map_err(FabricError::ZenohError)?;
+        let Ok(Ok(reply)) = tokio::time::timeout(query_timeout, replies.recv_async()).await else {
+            return Ok(None);
+        };
+        let sample = reply.sample.map_err(|e| {
+            FabricError::Other(format!("Node {} state query failed: {}", node_id, e))
+        })?;
+        if self.oversized(&sample) {
+            return Ok(None);
+        }
+        let node_data: NodeData = decode_value(&sample.value)?;
+
+        if self.is_allowed_type(&node_data.node_type) {
+            self.nodes
+                .lock()
+                .await
+                .entry(node_id.to_string())
+                .or_insert_with(|| NodeState::new(node_data.clone()));
+        }
+        Ok(Some(node_data))
+    }
+
     pub async fn check_node_health(&self) {
-        let mut nodes = self.nodes.lock().await;
-        for (node_id, node_state) in nodes.iter_mut() {
-            let key = format!("node/{}/status", node_id);
-            match self.session.get(&key).res().await {
-                Ok(receiver) => {
-                    match receiver.recv_async().await {
-                        Ok(reply) => {
-                            if let Ok(sample) = reply.sample {
-                                if let Ok(status) =
-                                    std::str::from_utf8(&sample.value.payload.contiguous())
-                                {
-                                    node_state.last_value = NodeData::from_json(status).unwrap();
-                                    if node_state.last_value.status != "online" {
-                                        warn!("Node {} is offline", node_id);
-                                        node_state.last_value.status = "offline".to_string();
-                                        // Handle node failure, e.g., update node status, notify subscribers, etc.
-                                    }
-                                } else {
-                                    warn!("Failed to parse status for node {}", node_id);
-                                    node_state
-                                        .last_value
-                                        .set_status("unknown".to_owned())
-                                        .map_err(|e| warn!("Failed to set status: {}", e))
-                                        .ok();
-                                }
-                            } else {
-                                warn!("No sample available for node {}", node_id);
-                                node_state
-                                    .last_value
-                                    .set_status("unknown".to_owned())
-                                    .map_err(|e| warn!("Failed to set status: {}", e))
-                                    .ok();
-                            }
-                        }
-                        Err(e) => {
-                            warn!("Failed to receive reply for node {}: {}", node_id, e);
-                            node_state
-                                .last_value
-                                .set_status("unknown".to_owned())
-                                .map_err(|e| warn!("Failed to set status: {}", e))
-                                .ok();
-                        }
+        // Query without holding the node table so status ingestion and callbacks keep
+        // running while a slow node is waited on
+        let node_ids: Vec<String> = self.nodes.lock().await.keys().cloned().collect();
+        for node_id in node_ids {
+            let reported = self.query_node_health(&node_id).await;
+            let mut nodes = self.nodes.lock().await;
+            // Removed while its query was in flight
+            let Some(node_state) = nodes.get_mut(&node_id) else {
+                continue;
+            };
+            match reported {
+                Some(node_data) => {
+                    node_state.last_value = node_data;
+                    if node_state.last_value.status != NodeStatus::Online {
+                        warn!("Node {} is offline", node_id);
+                        node_state.set_status(NodeStatus::Offline);
+                        node_state.offline_reason =
+                            Some(OFFLINE_REASON_LIVELINESS_LOST.to_string());
                     }
                 }
-                Err(err) => {
-                    warn!("Failed to get status for node {}: {}", node_id, err);
+                None => {
                     node_state
                         .last_value
-                        .set_status("unknown".to_owned())
+                        .set_status(NodeStatus::Unknown)
                         .map_err(|e| warn!("Failed to set status: {}", e))
                         .ok();
                 }
@@ -287,36 +1049,241 @@ impl Orchestrator {
         sleep(Duration::from_secs(1)).await; // Adjust the interval as needed
     }
 
+    // The status a node reports when asked; None when it doesn't answer within
+    // health_query_timeout or its reply can't be decoded
+    async fn query_node_health(&self, node_id: &str) -> Option<NodeData> {
+        let key = self.node_key(node_id, "status");
+        let query_timeout = self.config.health_query_timeout;
+        let receiver = match self.session.get(&key).timeout(query_timeout).res().await {
+            Ok(receiver) => receiver,
+            Err(err) => {
+                warn!("Failed to get status for node {}: {}", node_id, err);
+                return None;
+            }
+        };
+        let reply = match tokio::time::timeout(query_timeout, receiver.recv_async()).await {
+            Ok(Ok(reply)) => reply,
+            Ok(Err(e)) => {
+                warn!("Failed to receive reply for node {}: {}", node_id, e);
+                return None;
+            }
+            Err(_) => {
+                warn!(
+                    "Timed out after {:?} waiting for status of node {}",
+                    query_timeout, node_id
+                );
+                return None;
+            }
+        };
+        let Some(sample) = reply.sample.ok().filter(|sample| !self.oversized(sample)) else {
+            warn!("No sample available for node {}", node_id);
+            return None;
+        };
+        // Replies may be CBOR as well as JSON, and come from the network, so a bad one
+        // is reported rather than trusted
+        match decode_value::<serde_json::Value>(&sample.value)
+            .and_then(|json_value| NodeData::from_json(&json_value.to_string()))
+        {
+            Ok(node_data) => Some(node_data),
+            Err(e) => {
+                warn!("Failed to parse status for node {}: {:?}", node_id, e);
+                None
+            }
+        }
+    }
+
+    // Publish a JSON merge patch that the node applies on top of its current config
     pub async fn update_node_config(&self, node_id: &str, config: Value) -> Result<()> {
-        let key = format!("fabric/{}/config", node_id);
-        let config_json = serde_json::to_string(&config).map_err(FabricError::SerdeJsonError)?;
-        let mut backoff = ExponentialBackoff::default();
+        self.ensure_leader()?;
+        let key = self.node_key(node_id, "config/merge");
+        let payload = self.encode_payload(&config)?;
 
-        loop {
-            match self.session.put(&key, config_json.clone()).res().await {
-                Ok(_) => return Ok(()),
-                Err(err) => {
-                    if let Some(duration) = backoff.next_backoff() {
-                        warn!(
-                            "Failed to update node config, retrying in {:?}: {}",
-                            duration, err
-                        );
-                        sleep(duration).await;
-                    } else {
-                        return Err(FabricError::Other(format!(
-                            "Failed to update node config: {}",
-                            err
-                        )));
-                    }
-                }
+        self.config
+            .publish_retry
+            .retry_if(
+                "update node config",
+                || async {
+                    self.session
+                        .put(&key, payload.clone())
+                        .res()
+                        .await
+                        .map_err(FabricError::ZenohError)
+                },
+                FabricError::is_retryable,
+            )
+            .await
+            .map_err(|err| FabricError::Other(format!("Failed to update node config: {}", err)))
+    }
+
+    // Publish RFC 6902 operations; the node applies all of them or, if one fails, none
+    pub async fn apply_json_patch(&self, node_id: &str, patch: Vec<PatchOp>) -> Result<()> {
+        self.ensure_leader()?;
+        let key = self.node_key(node_id, "config/patch");
+        let payload = self.encode_payload(&patch)?;
+
+        self.config
+            .publish_retry
+            .retry_if(
+                "apply json patch",
+                || async {
+                    self.session
+                        .put(&key, payload.clone())
+                        .res()
+                        .await
+                        .map_err(FabricError::ZenohError)
+                },
+                FabricError::is_retryable,
+            )
+            .await
+            .map_err(|err| FabricError::Other(format!("Failed to apply json patch: {}", err)))
+    }
+
+    // Deliver an event to one node, queueing it while the node is offline so it is
+    // handed over in order once the node reports online again
+    pub async fn send_command(&self, node_id: &str, kind: &str, payload: Value) -> Result<()> {
+        let message = BroadcastMessage {
+            kind: kind.to_string(),
+            payload,
+        };
+        let mut queues = self.command_queues.lock().await;
+        let queue = queues.entry(node_id.to_string()).or_default();
+        if queue.is_empty() && self.is_node_online(node_id).await {
+            return self.publish_command(node_id, &message).await;
+        }
+        info!("Node {} is offline, queueing command {}", node_id, kind);
+        queue.push_back(QueuedCommand {
+            message,
+            queued_at: Instant::now(),
+        });
+        Ok(())
+    }
+
+    pub async fn queued_commands(&self, node_id: &str) -> usize {
+        self.command_queues
+            .lock()
+            .await
+            .get(node_id)
+            .map_or(0, VecDeque::len)
+    }
+
+    async fn flush_commands(&self, node_id: &str) {
+        let mut queues = self.command_queues.lock().await;
+        let Some(queue) = queues.get_mut(node_id) else {
+            return;
+        };
+        while let Some(command) = queue.pop_front() {
+            if command.queued_at.elapsed() > self.config.command_ttl {
+                warn!(
+                    "Dropping stale command {} for node {}",
+                    command.message.kind, node_id
+                );
+                self.metrics
+                    .commands_expired
+                    .fetch_add(1, Ordering::Relaxed);
+                continue;
+            }
+            if let Err(e) = self.publish_command(node_id, &command.message).await {
+                error!(
+                    "Failed to deliver queued command to node {}: {:?}",
+                    node_id, e
+                );
+                queue.push_front(command);
+                break;
             }
         }
     }
 
+    async fn publish_command(&self, node_id: &str, message: &BroadcastMessage) -> Result<()> {
+        let key = self.node_key(node_id, "command");
+        let message_json = serde_json::to_string(message)?;
+
+        self.config
+            .publish_retry
+            .retry_if(
+                "send command",
+                || async {
+                    self.session
+                        .put(&key, message_json.clone())
+                        .encoding(Encoding::APP_JSON)
+                        .res()
+                        .await
+                        .map_err(FabricError::ZenohError)
+                },
+                FabricError::is_retryable,
+            )
+            .await
+            .map_err(|err| FabricError::PublishError(format!("command {}: {}", message.kind, err)))
+    }
+
+    async fn is_node_online(&self, node_id: &str) -> bool {
+        self.nodes
+            .lock()
+            .await
+            .get(node_id)
+            .is_some_and(|state| state.last_value.status == NodeStatus::Online)
+    }
+
+    // Send an event to every running node, e.g. an emergency stop
+    pub async fn broadcast(&self, kind: &str, payload: Value) -> Result<()> {
+        let message = BroadcastMessage {
+            kind: kind.to_string(),
+            payload,
+        };
+        let message_json = serde_json::to_string(&message)?;
+
+        self.config
+            .publish_retry
+            .retry_if(
+                "broadcast",
+                || async {
+                    self.session
+                        .put(keys::broadcast_key(self.namespace()), message_json.clone())
+                        .encoding(Encoding::APP_JSON)
+                        .res()
+                        .await
+                        .map_err(FabricError::ZenohError)
+                },
+                FabricError::is_retryable,
+            )
+            .await
+            .map_err(|err| FabricError::PublishError(format!("broadcast {}: {}", kind, err)))
+    }
+
+    // Stop ingesting statuses and serving configs, and undeclare all publishers and subscribers
+    pub async fn close(&self) -> Result<()> {
+        self.unsubscribe_from_node_statuses().await?;
+        self.unserve_node_configs().await?;
+        for (_, publisher) in self.publishers.write().await.drain() {
+            publisher
+                .zenoh_publisher
+                .undeclare()
+                .res()
+                .await
+                .map_err(FabricError::ZenohError)?;
+        }
+        for (_, subscriber) in self.subscribers.write().await.drain() {
+            subscriber.zenoh_subscriber.undeclare().await?;
+        }
+        info!("Orchestrator {} closed", self.id);
+        Ok(())
+    }
+
+    fn namespace(&self) -> &str {
+        &self.config.namespace
+    }
+
+    fn node_key(&self, node_id: &str, suffix: &str) -> String {
+        keys::node_key(self.namespace(), node_id, suffix)
+    }
+
     pub fn get_id(&self) -> &str {
         &self.id
     }
 
+    pub async fn get_published_config(&self, node_id: &str) -> Option<NodeConfig> {
+        self.published_configs.lock().await.get(node_id).cloned()
+    }
+
     pub async fn register_callback(
         &self,
         node_id: &str,
@@ -327,33 +1294,113 @@ impl Orchestrator {
         Ok(())
     }
 
+    // Like register_callback, but also passes the value the update replaced
+    pub async fn register_transition_callback(
+        &self,
+        node_id: &str,
+        callback: TransitionCallback,
+    ) -> Result<()> {
+        let mut callbacks = self.transition_callbacks.lock().await;
+        callbacks.insert(node_id.to_string(), callback);
+        Ok(())
+    }
+
+    // Returns whether a callback of either kind was registered for the node
+    pub async fn unregister_callback(&self, node_id: &str) -> Result<bool> {
+        let removed = self.callbacks.lock().await.remove(node_id).is_some();
+        let removed_transition = self
+            .transition_callbacks
+            .lock()
+            .await
+            .remove(node_id)
+            .is_some();
+        Ok(removed || removed_transition)
+    }
+
+    // Fire for every node whose id matches a key expression; a `*` inside a chunk,
+    // as in "sensor_*", matches any run of characters
+    pub async fn register_pattern_callback(
+        &self,
+        pattern: &str,
+        callback: Arc<Mutex<dyn Fn(NodeData) + Send + Sync>>,
+    ) -> Result<()> {
+        let key_expr = OwnedKeyExpr::autocanonize(node_id_pattern(pattern)).map_err(|e| {
+            FabricError::InvalidConfig(format!("invalid pattern {}: {}", pattern, e))
+        })?;
+        self.pattern_callbacks
+            .lock()
+            .await
+            .push((key_expr, callback));
+        Ok(())
+    }
+
+    pub async fn clear_callbacks(&self) {
+        self.callbacks.lock().await.clear();
+        self.transition_callbacks.lock().await.clear();
+        self.pattern_callbacks.lock().await.clear();
+    }
+
+    // Like register_callback, but a known node's last value is delivered right away so
+    // consumers start from a snapshot instead of waiting for the next update
+    pub async fn subscribe_to_node(
+        &self,
+        node_id: &str,
+        callback: Arc<Mutex<dyn Fn(NodeData) + Send + Sync>>,
+    ) -> Result<()> {
+        // Holding the nodes lock keeps a concurrent update from overtaking the snapshot
+        let nodes = self.nodes.lock().await;
+        self.register_callback(node_id, callback.clone()).await?;
+        if let Some(node_state) = nodes.get(node_id) {
+            let callback = callback.lock().await;
+            callback(node_state.callback_data());
+        }
+        Ok(())
+    }
+
     async fn check_offline_nodes(&self) {
         let mut nodes = self.nodes.lock().await;
         let now = SystemTime::now();
         for (node_id, node_state) in nodes.iter_mut() {
-            if node_state.last_value.status == "online" {
-                if let Ok(duration) = now.duration_since(node_state.last_update) {
-                    if duration > Duration::from_secs(10) {
-                        warn!("Node {} has not sent a status update in 10 seconds, marking as offline", node_id);
-                        node_state.last_value.status = "offline".to_string();
-
-                        // Trigger callbacks for the status change
-                        let callbacks = self.callbacks.lock().await;
-                        if let Some(callback) = callbacks.get(node_id) {
-                            let callback = callback.lock().await;
-                            callback(node_state.last_value.clone());
-                        }
-                    }
-                }
+            let previous = node_state.last_value.clone();
+            let timed_out = node_state.last_value.status == NodeStatus::Online
+                && now
+                    .duration_since(node_state.last_update)
+                    .is_ok_and(|duration| duration > self.config.offline_timeout);
+            if timed_out {
+                warn!(
+                    "Node {} has not sent a status update in {:?}, marking as offline",
+                    node_id, self.config.offline_timeout
+                );
+                node_state.set_status(NodeStatus::Offline);
+                node_state.offline_reason = Some(OFFLINE_REASON_HEARTBEAT_TIMEOUT.to_string());
+                self.metrics
+                    .offline_transitions_total
+                    .fetch_add(1, Ordering::Relaxed);
+            } else if !node_state.has_pending_status() {
+                continue;
+            }
+
+            // Trigger callbacks for the status change once it has settled
+            if self.debounce_status(node_state) {
+                self.fire_callbacks(node_id, Some(previous), node_state.callback_data())
+                    .await;
             }
         }
     }
 
     pub async fn create_publisher(&self, topic: String) -> Result<()> {
+        self.create_publisher_with_options(topic, PublisherOptions::default())
+            .await
+    }
+
+    pub async fn create_publisher_with_options(
+        &self,
+        topic: String,
+        options: PublisherOptions,
+    ) -> Result<()> {
         let key_expr = topic.clone();
-        let zenoh_publisher = self
-            .session
-            .declare_publisher(key_expr)
+        let zenoh_publisher = options
+            .apply(self.session.declare_publisher(key_expr))
             .res()
             .await
             .map_err(FabricError::ZenohError)?;
@@ -371,6 +1418,9 @@ impl Orchestrator {
     pub async fn publish(&self, topic: &str, data: Vec<u8>) -> Result<()> {
         let publishers = self.publishers.read().await;
         if let Some(publisher) = publishers.get(topic) {
+            self.metrics
+                .published_payload_bytes
+                .record(data.len() as u64);
             publisher
                 .zenoh_publisher
                 .put(data)
@@ -379,10 +1429,7 @@ impl Orchestrator {
                 .map_err(FabricError::ZenohError)?;
             Ok(())
         } else {
-            Err(FabricError::Other(format!(
-                "Publisher not found for topic: {}",
-                topic
-            )))
+            Err(FabricError::PublisherNotFound(topic.to_string()))
         }
     }
 
@@ -390,28 +1437,66 @@ impl Orchestrator {
         &self,
         topic: String,
         callback: Arc<Mutex<dyn Fn(Sample) + Send + Sync>>,
+    ) -> Result<()> {
+        self.create_subscriber_with_options(topic, callback, SubscriberOptions::default())
+            .await
+    }
+
+    pub async fn create_subscriber_with_options(
+        &self,
+        topic: String,
+        callback: Arc<Mutex<dyn Fn(Sample) + Send + Sync>>,
+        options: SubscriberOptions,
     ) -> Result<()> {
         let key_expr = topic.clone();
         let subscriber_tx = self.subscriber_tx.clone();
-        let zenoh_subscriber = self
-            .session
-            .declare_subscriber(&key_expr)
-            .callback(move |sample| {
-                let tx = subscriber_tx.clone();
-                tokio::spawn(async move {
-                    if let Err(e) = tx.send(sample).await {
-                        error!("Failed to send sample to handler: {:?}", e);
+        let metrics = self.metrics.clone();
+        let max_payload_bytes = options.max_payload_bytes;
+        let zenoh_subscriber =
+            ZenohSubscriber::declare(&self.session, &key_expr, options.mode, move |sample| {
+                if exceeds_max_payload(&sample, max_payload_bytes) {
+                    let dropped = metrics.oversized_dropped.fetch_add(1, Ordering::Relaxed) + 1;
+                    warn!(
+                        "Dropping oversized sample on {} ({} bytes, {} dropped so far)",
+                        sample.key_expr,
+                        sample.value.payload.len(),
+                        dropped
+                    );
+                    return;
+                }
+                match subscriber_tx.try_send(sample) {
+                    Ok(()) => {}
+                    Err(mpsc::error::TrySendError::Full(sample)) => {
+                        let dropped = metrics.ingest_dropped.fetch_add(1, Ordering::Relaxed) + 1;
+                        if dropped % 100 == 1 {
+                            warn!(
+                                "Ingest queue full, dropped sample on {} ({} dropped so far)",
+                                sample.key_expr, dropped
+                            );
+                        }
                     }
-                });
+                    Err(mpsc::error::TrySendError::Closed(_)) => {
+                        error!("Failed to send sample to handler: channel closed");
+                    }
+                }
             })
-            .res()
-            .await
-            .map_err(FabricError::ZenohError)?;
+            .await?;
+
+        let (dispatch_tx, mut dispatch_rx) = mpsc::channel::<Sample>(self.config.ingest_capacity);
+        tokio::spawn(async move {
+            while let Some(sample) = dispatch_rx.recv().await {
+                let callback = callback.lock().await;
+                callback(sample);
+            }
+        });
 
         let subscriber = Subscriber {
             topic: topic.clone(),
-            callback,
+            dispatch_tx,
             zenoh_subscriber,
+            dedup: options
+                .dedup_window
+                .map(|window| std::sync::Mutex::new(Deduplicator::new(window))),
         };
 
         debug!("Created subscriber for topic: {}", subscriber.topic);
@@ -420,23 +1505,292 @@ impl Orchestrator {
         Ok(())
     }
 
+    // Fetch the latest sample per key held for a pull-mode subscriber; it reaches the
+    // subscriber's callback like a pushed sample would
+    pub async fn pull_next(&self, topic: &str) -> Result<()> {
+        let subscribers = self.subscribers.read().await;
+        let subscriber = subscribers.get(topic).ok_or_else(|| {
+            FabricError::Other(format!("Subscriber not found for topic: {}", topic))
+        })?;
+        subscriber.zenoh_subscriber.pull().await
+    }
+
+    // Stop processing telemetry without tearing down subscriptions; samples that
+    // arrive while paused are dropped and counted
+    pub fn pause(&self) {
+        info!("Pausing ingestion on orchestrator {}", self.id);
+        self.paused.store(true, Ordering::SeqCst);
+    }
+
+    pub fn resume(&self) {
+        info!("Resuming ingestion on orchestrator {}", self.id);
+        self.paused.store(false, Ordering::SeqCst);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    fn drop_if_paused(&self) -> bool {
+        if !self.is_paused() {
+            return false;
+        }
+        self.metrics.paused_dropped.fetch_add(1, Ordering::Relaxed);
+        true
+    }
+
+    fn is_allowed_type(&self, node_type: &str) -> bool {
+        self.config.allowed_node_types.is_empty()
+            || self.config.allowed_node_types.contains(node_type)
+    }
+
+    async fn handle_status_samples(&self, mut rx: mpsc::Receiver<Sample>) {
+        while let Some(sample) = rx.recv().await {
+            self.update_node_health(sample).await;
+        }
+    }
+
     async fn handle_subscriber_samples(&self, mut rx: mpsc::Receiver<Sample>) {
         while let Some(sample) = rx.recv().await {
-            let subscribers = self.subscribers.read().await;
-            for subscriber in subscribers.values() {
-                if subscriber
-                    .zenoh_subscriber
-                    .key_expr()
-                    .intersects(sample.key_expr.as_keyexpr())
-                {
-                    let callback = subscriber.callback.lock().await;
-                    callback(sample.clone());
+            if self.drop_if_paused() {
+                continue;
+            }
+            // Route under the lock, deliver after releasing it so a slow callback
+            // holds up neither other subscribers nor create/unsubscribe
+            let targets: Vec<_> = {
+                let subscribers = self.subscribers.read().await;
+                subscribers
+                    .values()
+                    .filter(|subscriber| subscriber.accepts(&sample))
+                    .map(|subscriber| subscriber.dispatch_tx.clone())
+                    .collect()
+            };
+            for dispatch_tx in targets {
+                match dispatch_tx.try_send(sample.clone()) {
+                    Ok(()) => {}
+                    Err(mpsc::error::TrySendError::Full(sample)) => {
+                        let dropped =
+                            self.metrics.ingest_dropped.fetch_add(1, Ordering::Relaxed) + 1;
+                        if dropped % 100 == 1 {
+                            warn!(
+                                "Subscriber queue full, dropped sample on {} ({} dropped so far)",
+                                sample.key_expr, dropped
+                            );
+                        }
+                    }
+                    Err(mpsc::error::TrySendError::Closed(sample)) => {
+                        debug!(
+                            "Dropping sample on {} for a closed subscriber",
+                            sample.key_expr
+                        );
+                    }
                 }
             }
         }
     }
 
+    // Render the known topology as a Graphviz DOT graph, colored by node status
+    pub async fn export_dot(&self) -> String {
+        let nodes = self.nodes.lock().await;
+        let mut node_ids: Vec<&String> = nodes.keys().collect();
+        node_ids.sort();
+
+        let mut dot = String::from("digraph fabric {\n");
+        dot.push_str(&format!(
+            "    \"{}\" [shape=doublecircle, label=\"{}\"];\n",
+            escape_dot(&self.id),
+            escape_dot(&self.id)
+        ));
+        for node_id in node_ids {
+            let node_data = &nodes[node_id].last_value;
+            let color = match node_data.status {
+                NodeStatus::Online => "green",
+                NodeStatus::Offline => "red",
+                NodeStatus::Degraded | NodeStatus::Failed | NodeStatus::Unknown => "gray",
+            };
+            dot.push_str(&format!(
+                "    \"{}\" [label=\"{}\\n{}\\n{}\", color={}];\n",
+                escape_dot(node_id),
+                escape_dot(node_id),
+                escape_dot(&node_data.node_type),
+                node_data.status,
+                color
+            ));
+            dot.push_str(&format!(
+                "    \"{}\" -> \"{}\";\n",
+                escape_dot(&self.id),
+                escape_dot(node_id)
+            ));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    pub async fn metrics(&self) -> MetricsSnapshot {
+        let mut snapshot = self.metrics.snapshot();
+        for node_state in self.nodes.lock().await.values() {
+            match node_state.last_value.status {
+                NodeStatus::Online => snapshot.nodes_online += 1,
+                NodeStatus::Offline => snapshot.nodes_offline += 1,
+                _ => {}
+            }
+        }
+        snapshot
+    }
+
+    // Ids of known nodes whose last value matches, scanned under a single lock
+    pub async fn find_nodes(&self, predicate: impl Fn(&NodeData) -> bool) -> Vec<String> {
+        let nodes = self.nodes.lock().await;
+        let mut node_ids: Vec<String> = nodes
+            .iter()
+            .filter(|(_, node_state)| predicate(&node_state.last_value))
+            .map(|(node_id, _)| node_id.clone())
+            .collect();
+        node_ids.sort();
+        node_ids
+    }
+
+    pub async fn list_nodes_by_status(&self, status: NodeStatus) -> Vec<String> {
+        self.find_nodes(|node_data| node_data.status == status)
+            .await
+    }
+
+    pub async fn list_nodes_by_type(&self, node_type: &str) -> Vec<String> {
+        self.find_nodes(|node_data| node_data.node_type == node_type)
+            .await
+    }
+
+    // Nodes whose numeric metadata `field` is below `value`; nodes without it never match
+    pub async fn find_by_metadata_lt(&self, field: &str, value: f64) -> Vec<String> {
+        self.find_nodes(|node_data| {
+            node_data
+                .metadata
+                .as_ref()
+                .and_then(|metadata| metadata.get(field))
+                .and_then(Value::as_f64)
+                .is_some_and(|field_value| field_value < value)
+        })
+        .await
+    }
+
+    // Nodes matching a comma-separated label selector, e.g. "env=prod,gpu";
+    // `key=value` requires that value and a bare `key` only requires the label to exist
+    pub async fn select(&self, selector: &str) -> Vec<String> {
+        let requirements: Vec<(&str, Option<&str>)> = selector
+            .split(',')
+            .map(str::trim)
+            .filter(|term| !term.is_empty())
+            .map(|term| match term.split_once('=') {
+                Some((key, value)) => (key.trim(), Some(value.trim())),
+                None => (term, None),
+            })
+            .collect();
+
+        self.find_nodes(|node_data| {
+            requirements.iter().all(|(key, value)| {
+                node_data
+                    .labels
+                    .get(*key)
+                    .is_some_and(|label| value.is_none_or(|value| label == value))
+            })
+        })
+        .await
+    }
+
+    // Count and skip payloads over the configured limit before they are decoded
+    fn oversized(&self, sample: &Sample) -> bool {
+        if !exceeds_max_payload(sample, self.config.max_payload_bytes) {
+            return false;
+        }
+        let dropped = self
+            .metrics
+            .oversized_dropped
+            .fetch_add(1, Ordering::Relaxed)
+            + 1;
+        warn!(
+            "Dropping oversized sample on {} ({} bytes, {} dropped so far)",
+            sample.key_expr,
+            sample.value.payload.len(),
+            dropped
+        );
+        true
+    }
+
     pub async fn get_nodes(&self) -> HashMap<String, NodeState> {
         self.nodes.lock().await.clone()
     }
+
+    // One page of nodes sorted by id, plus the total node count; only the page is cloned
+    pub async fn get_nodes_page(
+        &self,
+        offset: usize,
+        limit: usize,
+    ) -> (Vec<(String, NodeState)>, usize) {
+        let nodes = self.nodes.lock().await;
+        let mut node_ids: Vec<&String> = nodes.keys().collect();
+        node_ids.sort();
+        let page = node_ids
+            .into_iter()
+            .skip(offset)
+            .take(limit)
+            .map(|node_id| (node_id.clone(), nodes[node_id].clone()))
+            .collect();
+        (page, nodes.len())
+    }
+
+    // Write the node table as JSON for crash recovery; the rename keeps a crash
+    // mid-write from clobbering the previous snapshot
+    pub async fn snapshot_to(&self, path: &Path) -> Result<()> {
+        let _snapshot_guard = self.snapshot_lock.lock().await;
+        let json = serde_json::to_vec_pretty(&*self.nodes.lock().await)?;
+        persistence::write_atomic(path, &json).await?;
+        info!(
+            "Orchestrator {} wrote node snapshot to {}",
+            self.id,
+            path.display()
+        );
+        Ok(())
+    }
+
+    // Nodes already heard from since startup keep their live state. Restored nodes
+    // that went quiet longer than offline_timeout ago come back offline.
+    pub async fn restore_from(&self, path: &Path) -> Result<()> {
+        let restored: HashMap<String, NodeState> =
+            serde_json::from_slice(&tokio::fs::read(path).await?)?;
+        let now = SystemTime::now();
+        let mut nodes = self.nodes.lock().await;
+        let count = restored.len();
+        for (node_id, mut node_state) in restored {
+            let stale = now
+                .duration_since(node_state.last_update)
+                .is_ok_and(|elapsed| elapsed > self.config.offline_timeout);
+            if stale && node_state.last_value.status != NodeStatus::Offline {
+                node_state.set_status(NodeStatus::Offline);
+                node_state.offline_reason = Some(OFFLINE_REASON_HEARTBEAT_TIMEOUT.to_string());
+            }
+            nodes.entry(node_id).or_insert(node_state);
+        }
+        info!(
+            "Orchestrator {} restored {} nodes from {}",
+            self.id,
+            count,
+            path.display()
+        );
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Component for Orchestrator {
+    fn name(&self) -> String {
+        format!("orchestrator {}", self.id)
+    }
+
+    async fn close(&self) -> Result<()> {
+        Orchestrator::close(self).await
+    }
+}
+
+fn escape_dot(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
 }
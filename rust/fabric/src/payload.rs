@@ -0,0 +1,152 @@
+use crate::error::{FabricError, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use zenoh::buffers::ZBuf;
+use zenoh::prelude::r#async::*;
+
+// Wire formats for fabric payloads; JSON stays the default for Python interop
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PayloadEncoding {
+    #[default]
+    Json,
+    Cbor,
+}
+
+impl PayloadEncoding {
+    pub const ALL: [PayloadEncoding; 2] = [PayloadEncoding::Json, PayloadEncoding::Cbor];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PayloadEncoding::Json => "json",
+            PayloadEncoding::Cbor => "cbor",
+        }
+    }
+
+    pub fn zenoh_encoding(&self) -> Encoding {
+        match self {
+            PayloadEncoding::Json => Encoding::APP_JSON,
+            PayloadEncoding::Cbor => Encoding::from("application/cbor"),
+        }
+    }
+
+    pub fn from_zenoh(encoding: &Encoding) -> Option<PayloadEncoding> {
+        let encoding = encoding.to_string();
+        Self::ALL
+            .into_iter()
+            .find(|candidate| candidate.zenoh_encoding().to_string() == encoding)
+    }
+
+    pub fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>> {
+        match self {
+            PayloadEncoding::Json => Ok(serde_json::to_vec(value)?),
+            PayloadEncoding::Cbor => {
+                let mut payload = Vec::new();
+                ciborium::into_writer(value, &mut payload)
+                    .map_err(|e| FabricError::Other(format!("CBOR encode error: {}", e)))?;
+                Ok(payload)
+            }
+        }
+    }
+
+    pub fn decode<T: DeserializeOwned>(&self, payload: &ZBuf) -> Result<T> {
+        match self {
+            PayloadEncoding::Json => decode_json(payload),
+            PayloadEncoding::Cbor => ciborium::from_reader(SliceReader::new(payload))
+                .map_err(|e| FabricError::Other(format!("CBOR decode error: {}", e))),
+        }
+    }
+}
+
+// Decode with the encoding the sender declared, falling back to the other formats
+// for senders that didn't declare one or declared it wrongly
+pub fn decode_value<T: DeserializeOwned>(value: &zenoh::value::Value) -> Result<T> {
+    let declared = PayloadEncoding::from_zenoh(&value.encoding).unwrap_or_default();
+    declared.decode(&value.payload).or_else(|err| {
+        PayloadEncoding::ALL
+            .into_iter()
+            .filter(|fallback| *fallback != declared)
+            .find_map(|fallback| fallback.decode(&value.payload).ok())
+            .ok_or(err)
+    })
+}
+
+// Key on which consumers preferring `encoding` receive transcoded copies of `topic`
+pub fn negotiated_key(topic: &str, encoding: PayloadEncoding) -> String {
+    format!("{}/@{}", topic, encoding.name())
+}
+
+// Deserialize JSON without first copying a fragmented payload into one buffer.
+// Single-slice payloads are already contiguous, so they take the faster slice path.
+pub fn decode_json<T: DeserializeOwned>(payload: &ZBuf) -> Result<T> {
+    let mut slices = payload.slices();
+    match (slices.next(), slices.next()) {
+        (Some(slice), None) => Ok(serde_json::from_slice(slice)?),
+        (None, _) => Ok(serde_json::from_slice(&[])?),
+        _ => Ok(serde_json::from_reader(SliceReader::new(payload))?),
+    }
+}
+
+// Reads a payload slice by slice. Zenoh's own ZBufReader reports end of input as
+// an error rather than Ok(0), which std::io consumers treat as a failure.
+struct SliceReader<'a> {
+    slices: <ZBuf as SplitBuffer>::Slices<'a>,
+    current: &'a [u8],
+}
+
+impl<'a> SliceReader<'a> {
+    fn new(payload: &'a ZBuf) -> Self {
+        Self {
+            slices: payload.slices(),
+            current: &[],
+        }
+    }
+}
+
+impl Read for SliceReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        while self.current.is_empty() {
+            match self.slices.next() {
+                Some(slice) => self.current = slice,
+                None => return Ok(0),
+            }
+        }
+        self.current.read(buf)
+    }
+}
+
+// Round every float in `value` to `significant_digits`, leaving integers untouched
+pub fn round_floats(value: &mut serde_json::Value, significant_digits: u32) {
+    match value {
+        serde_json::Value::Number(number) if number.is_f64() => {
+            if let Some(rounded) = number
+                .as_f64()
+                .map(|float| round_significant(float, significant_digits))
+                .and_then(serde_json::Number::from_f64)
+            {
+                *number = rounded;
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for item in items {
+                round_floats(item, significant_digits);
+            }
+        }
+        serde_json::Value::Object(object) => {
+            for item in object.values_mut() {
+                round_floats(item, significant_digits);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn round_significant(value: f64, significant_digits: u32) -> f64 {
+    if value == 0.0 || !value.is_finite() || significant_digits == 0 {
+        return value;
+    }
+    let magnitude = value.abs().log10().floor() as i32;
+    let scale = 10f64.powi(significant_digits as i32 - 1 - magnitude);
+    (value * scale).round() / scale
+}
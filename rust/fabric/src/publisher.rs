@@ -0,0 +1,51 @@
+use zenoh::prelude::r#async::*;
+use zenoh::publication::PublisherBuilder;
+
+// Express trades throughput for latency: samples go out at real-time priority, ahead of
+// the batching queues of lower priorities. Use it for small, latency-critical messages;
+// bulk telemetry is better served batched. Zenoh 0.11 has no per-message express flag,
+// so this maps to priority only. Whether congested samples are dropped or block the
+// sender is a separate choice, left at zenoh's default unless set.
+#[derive(Clone, Debug, Default)]
+pub struct PublisherOptions {
+    pub express: bool,
+    pub congestion_control: CongestionControl,
+}
+
+impl PublisherOptions {
+    pub fn priority(&self) -> Priority {
+        if self.express {
+            Priority::RealTime
+        } else {
+            Priority::default()
+        }
+    }
+
+    pub(crate) fn apply<'a, 'b>(
+        &self,
+        builder: PublisherBuilder<'a, 'b>,
+    ) -> PublisherBuilder<'a, 'b> {
+        builder
+            .priority(self.priority())
+            .congestion_control(self.congestion_control)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_express_raises_priority_only() {
+        let options = PublisherOptions {
+            express: true,
+            ..Default::default()
+        };
+        assert_eq!(options.priority(), Priority::RealTime);
+        assert_eq!(options.congestion_control, CongestionControl::default());
+
+        let default = PublisherOptions::default();
+        assert_eq!(default.priority(), Priority::default());
+        assert_eq!(default.congestion_control, CongestionControl::default());
+    }
+}
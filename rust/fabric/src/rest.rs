@@ -0,0 +1,111 @@
+use crate::error::{FabricError, Result};
+use crate::node::interface::{NodeConfig, NodeData};
+use crate::orchestrator::Orchestrator;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use log::info;
+use serde_json::Value;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+
+struct RestError(FabricError);
+
+impl From<FabricError> for RestError {
+    fn from(error: FabricError) -> Self {
+        RestError(error)
+    }
+}
+
+impl IntoResponse for RestError {
+    fn into_response(self) -> Response {
+        let status = match &self.0 {
+            FabricError::InvalidConfig(_)
+            | FabricError::InvalidTransition(_, _)
+            | FabricError::SerdeJsonError(_)
+            | FabricError::SerdeYamlError(_) => StatusCode::BAD_REQUEST,
+            FabricError::PublisherNotFound(_) | FabricError::KeyNotFound(_) => {
+                StatusCode::NOT_FOUND
+            }
+            FabricError::NotLeader(_) => StatusCode::CONFLICT,
+            error if error.is_retryable() => StatusCode::SERVICE_UNAVAILABLE,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        let body = Json(serde_json::json!({ "error": self.0.to_string() }));
+        (status, body).into_response()
+    }
+}
+
+// Routes over an orchestrator's node table, for scripts and curl
+pub fn router(orchestrator: Arc<Orchestrator>) -> Router {
+    Router::new()
+        .route("/nodes", get(list_nodes))
+        .route("/nodes/:id", get(get_node))
+        .route("/nodes/:id/config", post(publish_config))
+        .route("/nodes/:id/events/:event", post(send_event))
+        .with_state(orchestrator)
+}
+
+pub async fn serve_rest(addr: SocketAddr, orchestrator: Arc<Orchestrator>) -> Result<()> {
+    let listener = TcpListener::bind(addr).await?;
+    serve_rest_listener(listener, orchestrator).await
+}
+
+// Serve on an already bound listener, e.g. one on an ephemeral port
+pub async fn serve_rest_listener(
+    listener: TcpListener,
+    orchestrator: Arc<Orchestrator>,
+) -> Result<()> {
+    info!("Serving REST API on {}", listener.local_addr()?);
+    axum::serve(listener, router(orchestrator)).await?;
+    Ok(())
+}
+
+async fn list_nodes(State(orchestrator): State<Arc<Orchestrator>>) -> Json<Vec<NodeData>> {
+    let nodes = orchestrator.get_nodes().await;
+    let mut node_data: Vec<NodeData> = nodes.values().map(|state| state.callback_data()).collect();
+    node_data.sort_by(|a, b| a.node_id.cmp(&b.node_id));
+    Json(node_data)
+}
+
+async fn get_node(
+    State(orchestrator): State<Arc<Orchestrator>>,
+    Path(node_id): Path<String>,
+) -> std::result::Result<Json<NodeData>, StatusCode> {
+    orchestrator
+        .get_nodes()
+        .await
+        .get(&node_id)
+        .map(|state| Json(state.callback_data()))
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+async fn publish_config(
+    State(orchestrator): State<Arc<Orchestrator>>,
+    Path(node_id): Path<String>,
+    Json(config): Json<NodeConfig>,
+) -> std::result::Result<StatusCode, RestError> {
+    if config.node_id != node_id {
+        return Err(FabricError::InvalidConfig(format!(
+            "config is for node {}, not {}",
+            config.node_id, node_id
+        ))
+        .into());
+    }
+    orchestrator.publish_node_config(&node_id, &config).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// The optional JSON body becomes the event payload; offline nodes get it on reconnect
+async fn send_event(
+    State(orchestrator): State<Arc<Orchestrator>>,
+    Path((node_id, event)): Path<(String, String)>,
+    payload: Option<Json<Value>>,
+) -> std::result::Result<StatusCode, RestError> {
+    let payload = payload.map_or(Value::Null, |Json(payload)| payload);
+    orchestrator.send_command(&node_id, &event, payload).await?;
+    Ok(StatusCode::ACCEPTED)
+}
@@ -0,0 +1,100 @@
+use crate::error::{FabricError, Result};
+use crate::orchestrator::RetryConfig;
+use backoff::backoff::Backoff;
+use log::{info, warn};
+use std::str::FromStr;
+use zenoh::config::{Config, WhatAmI};
+use zenoh::prelude::r#async::*;
+
+// Comma-separated endpoints, e.g. "tcp/router:7447,tcp/10.0.0.2:7447"
+pub const CONNECT_ENV: &str = "FABRIC_CONNECT";
+// "peer" or "client"
+pub const MODE_ENV: &str = "FABRIC_MODE";
+
+#[derive(Clone, Debug)]
+pub struct SessionOptions {
+    // Endpoints to connect to; empty relies on multicast scouting
+    pub connect: Vec<String>,
+    pub mode: WhatAmI,
+    // Attempts to open the session before giving up, including the first
+    pub max_attempts: u32,
+    pub retry: RetryConfig,
+}
+
+impl Default for SessionOptions {
+    fn default() -> Self {
+        Self {
+            connect: Vec::new(),
+            mode: WhatAmI::Peer,
+            max_attempts: 5,
+            retry: RetryConfig::default(),
+        }
+    }
+}
+
+impl SessionOptions {
+    // Defaults overridden by FABRIC_CONNECT and FABRIC_MODE
+    pub fn from_env() -> Self {
+        let mut options = Self::default();
+        if let Ok(connect) = std::env::var(CONNECT_ENV) {
+            options.connect = connect
+                .split(',')
+                .map(str::trim)
+                .filter(|endpoint| !endpoint.is_empty())
+                .map(str::to_string)
+                .collect();
+        }
+        if let Ok(mode) = std::env::var(MODE_ENV) {
+            match WhatAmI::from_str(&mode) {
+                Ok(mode) => options.mode = mode,
+                Err(_) => warn!("Ignoring invalid {} {:?}", MODE_ENV, mode),
+            }
+        }
+        options
+    }
+
+    pub fn zenoh_config(&self) -> Result<Config> {
+        let mut config = Config::default();
+        config
+            .set_mode(Some(self.mode))
+            .map_err(|_| FabricError::InvalidConfig(format!("invalid mode {}", self.mode)))?;
+        for endpoint in &self.connect {
+            config.connect.endpoints.push(endpoint.parse().map_err(|e| {
+                FabricError::InvalidConfig(format!("invalid endpoint {}: {}", endpoint, e))
+            })?);
+        }
+        Ok(config)
+    }
+}
+
+// Open a session, backing off between failed attempts, e.g. while the router starts
+pub async fn open_session(options: &SessionOptions) -> Result<Session> {
+    let mut backoff = options.retry.backoff();
+    let mut attempt = 1;
+    loop {
+        let error = match zenoh::open(options.zenoh_config()?).res().await {
+            Ok(session) => {
+                info!("Zenoh session opened with ZID: {}", session.zid());
+                return Ok(session);
+            }
+            Err(e) => e,
+        };
+        let delay = if attempt < options.max_attempts {
+            backoff.next_backoff()
+        } else {
+            None
+        };
+        let Some(delay) = delay else {
+            return Err(FabricError::Other(format!(
+                "Failed to open zenoh session after {} attempts: {}",
+                attempt, error
+            )));
+        };
+        warn!(
+            "Failed to open zenoh session (attempt {}/{}), retrying in {:?}: {}",
+            attempt, options.max_attempts, delay, error
+        );
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
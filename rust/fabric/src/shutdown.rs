@@ -0,0 +1,87 @@
+use crate::error::Result;
+use async_trait::async_trait;
+use futures::future::join_all;
+use log::{info, warn};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+// Anything that has to flush, report and undeclare before the process exits
+#[async_trait]
+pub trait Component: Send + Sync {
+    fn name(&self) -> String;
+    async fn close(&self) -> Result<()>;
+}
+
+// Components sharing one cancellation token; pass `token()` to their run loops
+#[derive(Clone, Default)]
+pub struct Components {
+    token: CancellationToken,
+    components: Vec<Arc<dyn Component>>,
+}
+
+impl Components {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+
+    pub fn register(&mut self, component: Arc<dyn Component>) {
+        self.components.push(component);
+    }
+}
+
+// Cancel the shared token, then close every component concurrently, giving each
+// at most `timeout`. Returns the names of components that failed or timed out.
+pub async fn graceful(components: &Components, timeout: Duration) -> Vec<String> {
+    components.token.cancel();
+
+    let closes = components.components.iter().map(|component| async move {
+        let name = component.name();
+        match tokio::time::timeout(timeout, component.close()).await {
+            Ok(Ok(())) => {
+                info!("{} stopped cleanly", name);
+                None
+            }
+            Ok(Err(e)) => {
+                warn!("{} failed to stop cleanly: {}", name, e);
+                Some(name)
+            }
+            Err(_) => {
+                warn!("{} did not stop within {:?}", name, timeout);
+                Some(name)
+            }
+        }
+    });
+
+    join_all(closes).await.into_iter().flatten().collect()
+}
+
+// Resolves on Ctrl+C, or SIGTERM on unix
+pub async fn wait_for_signal() -> Result<()> {
+    #[cfg(unix)]
+    {
+        let mut terminate =
+            tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())?;
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => result?,
+            _ = terminate.recv() => {}
+        }
+    }
+    #[cfg(not(unix))]
+    tokio::signal::ctrl_c().await?;
+    Ok(())
+}
+
+// Wait for a shutdown signal, then run the graceful sequence
+pub async fn on_signal(components: &Components, timeout: Duration) -> Result<Vec<String>> {
+    wait_for_signal().await?;
+    info!(
+        "Shutdown signal received, stopping {} components",
+        components.components.len()
+    );
+    Ok(graceful(components, timeout).await)
+}
@@ -0,0 +1,142 @@
+use crate::error::{FabricError, Result};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashSet, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use zenoh::prelude::r#async::*;
+use zenoh::subscriber::{PullSubscriber, Subscriber};
+
+pub const DEFAULT_MAX_PAYLOAD_BYTES: usize = 8 * 1024 * 1024;
+
+#[derive(Clone, Debug)]
+pub struct SubscriberOptions {
+    // Drop samples whose key and payload match one of the last N delivered
+    pub dedup_window: Option<usize>,
+    // Drop samples larger than this before they are buffered or deserialized
+    pub max_payload_bytes: usize,
+    pub mode: SubscriberMode,
+}
+
+impl Default for SubscriberOptions {
+    fn default() -> Self {
+        Self {
+            dedup_window: None,
+            max_payload_bytes: DEFAULT_MAX_PAYLOAD_BYTES,
+            mode: SubscriberMode::Push,
+        }
+    }
+}
+
+// Push delivers every sample as it arrives and suits control traffic and anything
+// that must not be missed. Pull keeps only the latest sample per key until the
+// consumer asks for it with pull_next, so a slow reader of high-rate telemetry
+// sees fresh values instead of working through a backlog.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SubscriberMode {
+    #[default]
+    Push,
+    Pull,
+}
+
+// Either flavour of zenoh subscriber; a pull subscriber doesn't expose its key
+// expression, so it is kept alongside
+pub(crate) struct ZenohSubscriber {
+    key_expr: KeyExpr<'static>,
+    inner: SubscriberInner,
+}
+
+enum SubscriberInner {
+    Push(Subscriber<'static, ()>),
+    Pull(PullSubscriber<'static, ()>),
+}
+
+impl ZenohSubscriber {
+    pub(crate) async fn declare<C>(
+        session: &Arc<Session>,
+        key_expr: &str,
+        mode: SubscriberMode,
+        callback: C,
+    ) -> Result<Self>
+    where
+        C: Fn(Sample) + Send + Sync + 'static,
+    {
+        let key_expr = KeyExpr::try_from(key_expr.to_string())?;
+        let builder = session.declare_subscriber(key_expr.clone());
+        let inner = match mode {
+            SubscriberMode::Push => SubscriberInner::Push(builder.callback(callback).res().await?),
+            SubscriberMode::Pull => {
+                SubscriberInner::Pull(builder.pull_mode().callback(callback).res().await?)
+            }
+        };
+        Ok(Self { key_expr, inner })
+    }
+
+    pub(crate) fn key_expr(&self) -> &KeyExpr<'static> {
+        &self.key_expr
+    }
+
+    // Samples fetched by a pull reach the callback like pushed ones
+    pub(crate) async fn pull(&self) -> Result<()> {
+        match &self.inner {
+            SubscriberInner::Push(_) => Err(FabricError::InvalidConfig(format!(
+                "Subscriber for {} is not in pull mode",
+                self.key_expr
+            ))),
+            SubscriberInner::Pull(subscriber) => Ok(subscriber.pull().res().await?),
+        }
+    }
+
+    pub(crate) async fn undeclare(self) -> Result<()> {
+        match self.inner {
+            SubscriberInner::Push(subscriber) => subscriber.undeclare().res().await?,
+            SubscriberInner::Pull(subscriber) => subscriber.undeclare().res().await?,
+        }
+        Ok(())
+    }
+}
+
+pub fn exceeds_max_payload(sample: &Sample, max_payload_bytes: usize) -> bool {
+    sample.value.payload.len() > max_payload_bytes
+}
+
+#[derive(Debug)]
+pub struct Deduplicator {
+    capacity: usize,
+    order: VecDeque<u64>,
+    seen: HashSet<u64>,
+}
+
+impl Deduplicator {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            order: VecDeque::with_capacity(capacity),
+            seen: HashSet::with_capacity(capacity),
+        }
+    }
+
+    // Returns true the first time a sample is observed within the window
+    pub fn observe(&mut self, sample: &Sample) -> bool {
+        let mut hasher = DefaultHasher::new();
+        sample.key_expr.as_str().hash(&mut hasher);
+        for slice in sample.value.payload.slices() {
+            hasher.write(slice);
+        }
+        let digest = hasher.finish();
+
+        if self.seen.contains(&digest) {
+            return false;
+        }
+        if self.capacity == 0 {
+            return true;
+        }
+        if self.order.len() == self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.seen.remove(&oldest);
+            }
+        }
+        self.order.push_back(digest);
+        self.seen.insert(digest);
+        true
+    }
+}
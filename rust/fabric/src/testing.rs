@@ -0,0 +1,159 @@
+use crate::error::Result;
+use crate::node::interface::{NodeConfig, NodeData, NodeFactory, NodeInterface};
+use async_trait::async_trait;
+use std::any::Any;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+#[derive(Default)]
+struct MockState {
+    reading: Option<serde_json::Value>,
+    events: Vec<(String, String)>,
+    applied_configs: Vec<NodeConfig>,
+}
+
+// Controllable NodeInterface for tests. Clones share state, so keep one clone to
+// inspect while the other is boxed into a `Node`.
+#[derive(Clone)]
+pub struct MockNode {
+    config: NodeConfig,
+    node_type: String,
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockNode {
+    pub fn new(config: NodeConfig) -> Self {
+        Self {
+            config,
+            node_type: "mock".to_string(),
+            state: Arc::default(),
+        }
+    }
+
+    pub fn with_type(mut self, node_type: &str) -> Self {
+        self.node_type = node_type.to_string();
+        self
+    }
+
+    pub fn with_reading(self, reading: serde_json::Value) -> Self {
+        self.set_reading(reading);
+        self
+    }
+
+    // Published as the metadata of every tick; no reading means nothing is published
+    pub fn set_reading(&self, reading: serde_json::Value) {
+        self.state.lock().unwrap().reading = Some(reading);
+    }
+
+    // (event, payload) pairs in the order they were handled
+    pub fn events(&self) -> Vec<(String, String)> {
+        self.state.lock().unwrap().events.clone()
+    }
+
+    // Every config passed to set_config or update_config, oldest first
+    pub fn applied_configs(&self) -> Vec<NodeConfig> {
+        self.state.lock().unwrap().applied_configs.clone()
+    }
+
+    fn record_config(&mut self, config: NodeConfig) {
+        self.state
+            .lock()
+            .unwrap()
+            .applied_configs
+            .push(config.clone());
+        self.config = config;
+    }
+}
+
+#[async_trait]
+impl NodeInterface for MockNode {
+    fn get_config(&self) -> NodeConfig {
+        self.config.clone()
+    }
+
+    async fn set_config(&mut self, config: NodeConfig) {
+        self.record_config(config);
+    }
+
+    fn get_type(&self) -> String {
+        self.node_type.clone()
+    }
+
+    async fn handle_event(&mut self, event: &str, payload: &str) -> Result<()> {
+        self.state
+            .lock()
+            .unwrap()
+            .events
+            .push((event.to_string(), payload.to_string()));
+        Ok(())
+    }
+
+    async fn update_config(&mut self, config: NodeConfig) {
+        self.record_config(config);
+    }
+
+    fn as_any(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    async fn tick(&mut self, _now: SystemTime) -> Result<Option<NodeData>> {
+        let reading = self.state.lock().unwrap().reading.clone();
+        Ok(reading.map(|reading| {
+            NodeData::builder(self.config.node_id.clone())
+                .node_type(self.node_type.clone())
+                .metadata(reading)
+                .build()
+        }))
+    }
+}
+
+// Hands out MockNodes and keeps a clone of each so tests can inspect them later
+#[derive(Clone, Default)]
+pub struct MockNodeFactory {
+    node_type: Option<String>,
+    reading: Option<serde_json::Value>,
+    created: Arc<Mutex<Vec<MockNode>>>,
+}
+
+impl MockNodeFactory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_type(mut self, node_type: &str) -> Self {
+        self.node_type = Some(node_type.to_string());
+        self
+    }
+
+    pub fn with_reading(mut self, reading: serde_json::Value) -> Self {
+        self.reading = Some(reading);
+        self
+    }
+
+    pub fn created(&self) -> Vec<MockNode> {
+        self.created.lock().unwrap().clone()
+    }
+
+    pub fn get(&self, node_id: &str) -> Option<MockNode> {
+        self.created
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|mock| mock.config.node_id == node_id)
+            .cloned()
+    }
+}
+
+impl NodeFactory for MockNodeFactory {
+    fn create(&self, config: NodeConfig) -> Box<dyn NodeInterface> {
+        let mut mock = MockNode::new(config);
+        if let Some(node_type) = &self.node_type {
+            mock = mock.with_type(node_type);
+        }
+        if let Some(reading) = &self.reading {
+            mock.set_reading(reading.clone());
+        }
+        self.created.lock().unwrap().push(mock.clone());
+        Box::new(mock)
+    }
+}
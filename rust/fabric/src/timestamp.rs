@@ -0,0 +1,96 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::de::{self, Deserializer, Visitor};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// How epoch-second timestamps are written on the wire
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimestampFormat {
+    #[default]
+    Epoch,
+    Rfc3339,
+}
+
+// Seconds since the Unix epoch; 0 if the clock is set before it
+pub fn epoch_seconds() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+pub fn to_rfc3339(timestamp: u64) -> Option<String> {
+    let seconds = i64::try_from(timestamp).ok()?;
+    DateTime::<Utc>::from_timestamp(seconds, 0)
+        .map(|datetime| datetime.to_rfc3339_opts(SecondsFormat::Secs, true))
+}
+
+pub fn from_rfc3339(value: &str) -> Option<u64> {
+    let datetime = DateTime::parse_from_rfc3339(value).ok()?;
+    u64::try_from(datetime.timestamp()).ok()
+}
+
+// For `#[serde(with = "fabric::timestamp::rfc3339")]` on epoch-second fields
+pub mod rfc3339 {
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(timestamp: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        match super::to_rfc3339(*timestamp) {
+            Some(value) => serializer.serialize_str(&value),
+            None => Err(serde::ser::Error::custom(format!(
+                "timestamp {} is out of range",
+                timestamp
+            ))),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        super::epoch_or_rfc3339(deserializer)
+    }
+}
+
+// For `#[serde(with = "fabric::timestamp::epoch_millis")]` on SystemTime fields
+pub mod epoch_millis {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    pub fn serialize<S: Serializer>(time: &SystemTime, serializer: S) -> Result<S::Ok, S::Error> {
+        let millis = time
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis() as u64);
+        serializer.serialize_u64(millis)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<SystemTime, D::Error> {
+        let millis = u64::deserialize(deserializer)?;
+        Ok(UNIX_EPOCH + Duration::from_millis(millis))
+    }
+}
+
+// Accepts either form, so readers don't care which format the writer chose
+pub fn epoch_or_rfc3339<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    struct TimestampVisitor;
+
+    impl Visitor<'_> for TimestampVisitor {
+        type Value = u64;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("epoch seconds or an RFC3339 string")
+        }
+
+        fn visit_u64<E: de::Error>(self, value: u64) -> Result<u64, E> {
+            Ok(value)
+        }
+
+        fn visit_i64<E: de::Error>(self, value: i64) -> Result<u64, E> {
+            u64::try_from(value).map_err(|_| E::custom(format!("negative timestamp {}", value)))
+        }
+
+        fn visit_str<E: de::Error>(self, value: &str) -> Result<u64, E> {
+            from_rfc3339(value).ok_or_else(|| E::custom(format!("invalid timestamp {}", value)))
+        }
+    }
+
+    deserializer.deserialize_any(TimestampVisitor)
+}
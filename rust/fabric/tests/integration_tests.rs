@@ -1,9 +1,24 @@
+use async_trait::async_trait;
 use fabric::error::FabricError;
 use fabric::init_logger;
-use fabric::node::interface::{NodeConfig, NodeData};
-use fabric::node::Node;
-use fabric::orchestrator::Orchestrator;
+use fabric::node::audit::{config_hash, AuditEntry};
+use fabric::node::interface::{NodeConfig, NodeData, NodeInterface, NodeStatus};
+use fabric::node::{Node, NodeOptions, StateMachine};
+use fabric::orchestrator::{
+    NetworkSnapshot, NodeState, Orchestrator, OrchestratorConfig, RetryConfig,
+    OFFLINE_REASON_HEARTBEAT_TIMEOUT,
+};
+use fabric::payload::PayloadEncoding;
+use fabric::publisher::PublisherOptions;
+use fabric::session::{open_session, SessionOptions};
+use fabric::shutdown::{self, Components};
+use fabric::subscriber::{SubscriberMode, SubscriberOptions};
+use fabric::timestamp::TimestampFormat;
+use futures::StreamExt;
 use log::{info, LevelFilter};
+use rand::Rng;
+use std::any::Any;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::{mpsc, Mutex};
 use tokio::time::{sleep, Duration};
@@ -243,10 +258,11 @@ async fn test_node_failure_and_recovery() -> fabric::Result<()> {
     {
         let nodes = orchestrator.nodes.lock().await;
         let node_state = nodes.get("failing_node").unwrap();
-        assert_eq!(node_state.last_value.status, "offline");
+        assert_eq!(node_state.last_value.status, NodeStatus::Offline);
     }
 
-    // Start node again
+    // Start node again; the old token is cancelled, so run would stop straight away
+    let node_cancel = CancellationToken::new();
     let node_clone = node.clone();
     let node_cancel_clone = node_cancel.clone();
     let node_handle = tokio::spawn(async move {
@@ -260,7 +276,7 @@ async fn test_node_failure_and_recovery() -> fabric::Result<()> {
     {
         let nodes = orchestrator.nodes.lock().await;
         let node_state = nodes.get("failing_node").unwrap();
-        assert_eq!(node_state.last_value.status, "online");
+        assert_eq!(node_state.last_value.status, NodeStatus::Online);
     }
 
     // Cancel orchestrator and node
@@ -296,10 +312,11 @@ async fn test_orchestrator_callback_functionality() -> Result<(), FabricError> {
     // Simulate node data update
     let node_data = NodeData {
         node_id: "test_node".to_string(),
-        status: "active".to_string(),
+        status: NodeStatus::Online,
         node_type: "radio".to_string(),
         timestamp: 1234567890,
         metadata: None,
+        labels: Default::default(),
     };
     orchestrator.update_node_state(node_data.clone()).await;
 
@@ -315,6 +332,47 @@ async fn test_orchestrator_callback_functionality() -> Result<(), FabricError> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_unregistered_callback_stops_firing() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator =
+        Orchestrator::new("test_unregister_orchestrator".to_string(), session.clone()).await?;
+    let (tx, mut rx) = mpsc::unbounded_channel();
+
+    orchestrator
+        .register_callback(
+            "unregister_node",
+            Arc::new(Mutex::new(move |node_data: NodeData| {
+                let _ = tx.send(node_data);
+            })),
+        )
+        .await?;
+    assert!(orchestrator.unregister_callback("unregister_node").await?);
+    assert!(!orchestrator.unregister_callback("unregister_node").await?);
+
+    orchestrator
+        .update_node_state(NodeData::from_fields(
+            "unregister_node".to_string(),
+            "generic".to_string(),
+            0,
+            None,
+            NodeStatus::Online,
+        ))
+        .await;
+    sleep(Duration::from_millis(200)).await;
+    assert!(rx.try_recv().is_err());
+
+    orchestrator
+        .register_callback("unregister_node", Arc::new(Mutex::new(|_: NodeData| {})))
+        .await?;
+    orchestrator.clear_callbacks().await;
+    assert!(!orchestrator.unregister_callback("unregister_node").await?);
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
 async fn test_multi_node_config_application() -> fabric::Result<()> {
     init_logger(LevelFilter::Info);
@@ -578,3 +636,4874 @@ async fn test_custom_message_publish_subscribe() -> fabric::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_node_fetches_config_on_boot() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator =
+        Orchestrator::new("test_fetch_orchestrator".to_string(), session.clone()).await?;
+
+    let orchestrator_cancel = CancellationToken::new();
+    let orchestrator_cancel_clone = orchestrator_cancel.clone();
+    let orchestrator_clone = orchestrator.clone();
+    let orchestrator_handle = tokio::spawn(async move {
+        orchestrator_clone
+            .run(orchestrator_cancel_clone)
+            .await
+            .unwrap();
+    });
+
+    wait_for_node_initialization().await;
+
+    // Publish the config before the node exists
+    let published_config = NodeConfig {
+        node_id: "fetching_node".to_string(),
+        config: serde_json::json!({"sampling_rate": 42}),
+    };
+    orchestrator
+        .publish_node_config(&published_config.node_id, &published_config)
+        .await?;
+
+    let node = Arc::new(
+        Node::with_options(
+            "fetching_node".to_string(),
+            "generic".to_string(),
+            NodeConfig {
+                node_id: "fetching_node".to_string(),
+                config: serde_json::json!({}),
+            },
+            session.clone(),
+            None,
+            NodeOptions {
+                fetch_config_timeout: Some(Duration::from_secs(1)),
+                ..Default::default()
+            },
+        )
+        .await?,
+    );
+
+    let node_cancel = CancellationToken::new();
+    let node_cancel_clone = node_cancel.clone();
+    let node_clone = node.clone();
+    let node_handle = tokio::spawn(async move {
+        node_clone.run(node_cancel_clone).await.unwrap();
+    });
+
+    wait_for_node_initialization().await;
+
+    assert_eq!(node.get_config().await, published_config);
+
+    orchestrator_cancel.cancel();
+    node_cancel.cancel();
+
+    let _ = tokio::time::timeout(Duration::from_secs(5), orchestrator_handle).await;
+    let _ = tokio::time::timeout(Duration::from_secs(5), node_handle).await;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_heartbeat_timeout_offline_reason() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator = Orchestrator::new(
+        "test_offline_reason_orchestrator".to_string(),
+        session.clone(),
+    )
+    .await?;
+    let (tx, mut rx) = mpsc::channel(100);
+
+    orchestrator
+        .register_callback(
+            "silent_node",
+            Arc::new(Mutex::new(move |node_data: NodeData| {
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    tx.send(node_data).await.unwrap();
+                });
+            })),
+        )
+        .await?;
+
+    // Seed a node whose last heartbeat is already stale
+    let mut node_state = NodeState::new(NodeData::new("silent_node".to_string()));
+    node_state.last_update = std::time::SystemTime::now() - Duration::from_secs(20);
+    orchestrator
+        .nodes
+        .lock()
+        .await
+        .insert("silent_node".to_string(), node_state);
+
+    let cancel = CancellationToken::new();
+    let cancel_clone = cancel.clone();
+    let orchestrator_clone = orchestrator.clone();
+    let orchestrator_handle = tokio::spawn(async move {
+        orchestrator_clone.run(cancel_clone).await.unwrap();
+    });
+
+    let received_data = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+        .await
+        .map_err(|_| FabricError::Other("Timeout waiting for callback".into()))?
+        .ok_or_else(|| FabricError::Other("Channel closed".into()))?;
+
+    assert_eq!(received_data.status, NodeStatus::Offline);
+    assert_eq!(
+        received_data.metadata.unwrap()["offline_reason"],
+        OFFLINE_REASON_HEARTBEAT_TIMEOUT
+    );
+    {
+        let nodes = orchestrator.nodes.lock().await;
+        assert_eq!(
+            nodes["silent_node"].offline_reason.as_deref(),
+            Some(OFFLINE_REASON_HEARTBEAT_TIMEOUT)
+        );
+    }
+
+    cancel.cancel();
+    let _ = tokio::time::timeout(Duration::from_secs(5), orchestrator_handle).await;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_config_republished_on_reconnect() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator = Orchestrator::with_config(
+        "test_reconnect_orchestrator".to_string(),
+        session.clone(),
+        OrchestratorConfig {
+            reconcile_on_reconnect: true,
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    let orchestrator_cancel = CancellationToken::new();
+    let orchestrator_cancel_clone = orchestrator_cancel.clone();
+    let orchestrator_clone = orchestrator.clone();
+    let orchestrator_handle = tokio::spawn(async move {
+        orchestrator_clone
+            .run(orchestrator_cancel_clone)
+            .await
+            .unwrap();
+    });
+
+    let initial_config = NodeConfig {
+        node_id: "restarting_node".to_string(),
+        config: serde_json::json!({}),
+    };
+    let desired_config = NodeConfig {
+        node_id: "restarting_node".to_string(),
+        config: serde_json::json!({"sampling_rate": 7}),
+    };
+
+    let node = Node::new(
+        "restarting_node".to_string(),
+        "generic".to_string(),
+        initial_config.clone(),
+        session.clone(),
+        None,
+    )
+    .await?;
+    let node_cancel = CancellationToken::new();
+    let node_cancel_clone = node_cancel.clone();
+    let node_handle = tokio::spawn(async move {
+        node.run(node_cancel_clone).await.unwrap();
+    });
+
+    wait_for_node_initialization().await;
+    orchestrator
+        .publish_node_config(&desired_config.node_id, &desired_config)
+        .await?;
+
+    // Stop the node and let in-flight heartbeats drain before marking it offline
+    node_cancel.cancel();
+    let _ = tokio::time::timeout(Duration::from_secs(5), node_handle).await;
+    sleep(Duration::from_millis(1500)).await;
+    orchestrator
+        .nodes
+        .lock()
+        .await
+        .get_mut("restarting_node")
+        .unwrap()
+        .last_value
+        .status = NodeStatus::Offline;
+
+    // A restarted node comes up with its compiled-in config
+    let restarted_node = Arc::new(
+        Node::new(
+            "restarting_node".to_string(),
+            "generic".to_string(),
+            initial_config,
+            session.clone(),
+            None,
+        )
+        .await?,
+    );
+    let restarted_cancel = CancellationToken::new();
+    let restarted_cancel_clone = restarted_cancel.clone();
+    let restarted_clone = restarted_node.clone();
+    let restarted_handle = tokio::spawn(async move {
+        restarted_clone.run(restarted_cancel_clone).await.unwrap();
+    });
+
+    wait_for_node_initialization().await;
+
+    assert_eq!(restarted_node.get_config().await, desired_config);
+
+    orchestrator_cancel.cancel();
+    restarted_cancel.cancel();
+
+    let _ = tokio::time::timeout(Duration::from_secs(5), orchestrator_handle).await;
+    let _ = tokio::time::timeout(Duration::from_secs(5), restarted_handle).await;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_publish_json_wraps_metadata() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let node = Node::with_options(
+        "json_node".to_string(),
+        "quadcopter".to_string(),
+        NodeConfig {
+            node_id: "json_node".to_string(),
+            config: serde_json::json!({}),
+        },
+        session.clone(),
+        None,
+        NodeOptions {
+            labels: HashMap::from([("env".to_string(), "prod".to_string())]),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    let (tx, mut rx) = mpsc::channel(100);
+    let topic = "node/json_node/telemetry";
+    node.create_subscriber(
+        topic.to_string(),
+        Arc::new(Mutex::new(move |sample: Sample| {
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                tx.send(sample).await.unwrap();
+            });
+        })),
+    )
+    .await?;
+    node.create_publisher(topic.to_string()).await?;
+
+    let telemetry = serde_json::json!({"altitude": 12.5, "battery_level": 80.0});
+    node.publish_json(topic, telemetry.clone()).await?;
+
+    let sample = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+        .await
+        .map_err(|_| FabricError::Other("Timeout waiting for telemetry".into()))?
+        .ok_or_else(|| FabricError::Other("Channel closed".into()))?;
+
+    assert_eq!(sample.value.encoding, Encoding::APP_JSON);
+    let node_data: NodeData = serde_json::from_slice(&sample.value.payload.contiguous())?;
+    assert_eq!(node_data.node_id, "json_node");
+    assert_eq!(node_data.node_type, "quadcopter");
+    assert_eq!(node_data.status, NodeStatus::Online);
+    assert!(node_data.timestamp > 0);
+    assert_eq!(node_data.metadata, Some(telemetry));
+    assert_eq!(node_data.labels["env"], "prod");
+
+    Ok(())
+}
+
+struct MetricsInterface {
+    config: NodeConfig,
+}
+
+#[async_trait]
+impl NodeInterface for MetricsInterface {
+    fn get_config(&self) -> NodeConfig {
+        self.config.clone()
+    }
+
+    async fn set_config(&mut self, config: NodeConfig) {
+        self.config = config;
+    }
+
+    fn get_type(&self) -> String {
+        "metrics".to_string()
+    }
+
+    async fn handle_event(&mut self, _event: &str, _payload: &str) -> fabric::Result<()> {
+        Ok(())
+    }
+
+    async fn update_config(&mut self, config: NodeConfig) {
+        self.config = config;
+    }
+
+    fn as_any(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn metrics(&self) -> serde_json::Value {
+        serde_json::json!({"packets_received": 42, "gps_fix_count": 3})
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_interface_metrics_in_telemetry() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let config = NodeConfig {
+        node_id: "metrics_node".to_string(),
+        config: serde_json::json!({}),
+    };
+    let node = Node::new(
+        "metrics_node".to_string(),
+        "metrics".to_string(),
+        config.clone(),
+        session.clone(),
+        Some(Box::new(MetricsInterface { config })),
+    )
+    .await?;
+
+    let (tx, mut rx) = mpsc::channel(100);
+    let topic = "node/metrics_node/telemetry";
+    node.create_subscriber(
+        topic.to_string(),
+        Arc::new(Mutex::new(move |sample: Sample| {
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                tx.send(sample).await.unwrap();
+            });
+        })),
+    )
+    .await?;
+    node.create_publisher(topic.to_string()).await?;
+
+    node.publish_json(topic, serde_json::json!({"altitude": 3.0}))
+        .await?;
+
+    let sample = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+        .await
+        .map_err(|_| FabricError::Other("Timeout waiting for telemetry".into()))?
+        .ok_or_else(|| FabricError::Other("Channel closed".into()))?;
+
+    let node_data: NodeData = serde_json::from_slice(&sample.value.payload.contiguous())?;
+    assert_eq!(
+        node_data.metadata,
+        Some(serde_json::json!({
+            "altitude": 3.0,
+            "packets_received": 42,
+            "gps_fix_count": 3
+        }))
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_orchestrator_ingest_drops_under_load() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator = Orchestrator::with_config(
+        "test_ingest_orchestrator".to_string(),
+        session.clone(),
+        OrchestratorConfig {
+            ingest_capacity: 10,
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    let processed = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let processed_clone = processed.clone();
+    orchestrator
+        .create_subscriber(
+            "test_ingest_flood".to_string(),
+            Arc::new(Mutex::new(move |_sample: Sample| {
+                // Deliberately slow consumer
+                std::thread::sleep(Duration::from_millis(20));
+                processed_clone.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            })),
+        )
+        .await?;
+
+    for i in 0..200 {
+        session
+            .put("test_ingest_flood", format!("sample {}", i))
+            .res()
+            .await?;
+    }
+
+    sleep(Duration::from_millis(500)).await;
+
+    let dropped = orchestrator.metrics().await.ingest_dropped;
+    let processed = processed.load(std::sync::atomic::Ordering::SeqCst) as u64;
+    assert!(dropped > 0);
+    assert!(processed + dropped <= 200);
+
+    // Status publications share the bounded policy instead of a task per sample
+    orchestrator.subscribe_to_node_statuses().await?;
+    let applied = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let applied_clone = applied.clone();
+    orchestrator
+        .register_callback(
+            "status_flood_node",
+            Arc::new(Mutex::new(move |node_data: NodeData| {
+                // Deliberately slow status handling
+                std::thread::sleep(Duration::from_millis(20));
+                applied_clone.lock().unwrap().push(node_data.timestamp);
+            })),
+        )
+        .await?;
+    sleep(Duration::from_millis(200)).await;
+
+    for i in 0..200 {
+        let node_data = NodeData::from_fields(
+            "status_flood_node".to_string(),
+            "generic".to_string(),
+            i,
+            // Distinct metadata, so no update is mistaken for a keepalive
+            Some(serde_json::json!({ "seq": i })),
+            NodeStatus::Online,
+        );
+        session
+            .put(
+                "fabric/status_flood_node/status",
+                serde_json::to_string(&node_data)?,
+            )
+            .res()
+            .await?;
+    }
+
+    sleep(Duration::from_millis(500)).await;
+
+    let status_dropped = orchestrator.metrics().await.ingest_dropped - dropped;
+    assert!(status_dropped > 0);
+    assert!(applied.lock().unwrap().len() as u64 + status_dropped <= 200);
+
+    // Whatever got through was applied in publication order
+    sleep(Duration::from_secs(1)).await;
+    let applied = applied.lock().unwrap().clone();
+    assert!(applied.len() > 1);
+    assert!(applied.windows(2).all(|pair| pair[0] < pair[1]));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_subscriber_dedup_drops_repeated_sample() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let node = Node::new(
+        "dedup_node".to_string(),
+        "generic".to_string(),
+        NodeConfig {
+            node_id: "dedup_node".to_string(),
+            config: serde_json::json!({}),
+        },
+        session.clone(),
+        None,
+    )
+    .await?;
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    node.create_subscriber_with_options(
+        "test_dedup_topic".to_string(),
+        Arc::new(Mutex::new(move |sample: Sample| {
+            tx.send(sample.value.payload.contiguous().to_vec()).unwrap();
+        })),
+        SubscriberOptions {
+            dedup_window: Some(16),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    // The same sample delivered twice, followed by a distinct one
+    session.put("test_dedup_topic", "reading 1").res().await?;
+    session.put("test_dedup_topic", "reading 1").res().await?;
+    session.put("test_dedup_topic", "reading 2").res().await?;
+
+    sleep(Duration::from_millis(500)).await;
+
+    let mut received = Vec::new();
+    while let Ok(payload) = rx.try_recv() {
+        received.push(payload);
+    }
+    assert_eq!(received, vec![b"reading 1".to_vec(), b"reading 2".to_vec()]);
+
+    Ok(())
+}
+
+#[derive(Default)]
+struct RecordingInterface {
+    config: Option<NodeConfig>,
+    set_calls: Arc<std::sync::atomic::AtomicUsize>,
+    update_calls: Arc<std::sync::atomic::AtomicUsize>,
+}
+
+#[async_trait]
+impl NodeInterface for RecordingInterface {
+    fn get_config(&self) -> NodeConfig {
+        self.config.clone().unwrap()
+    }
+
+    async fn set_config(&mut self, config: NodeConfig) {
+        self.set_calls
+            .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        self.config = Some(config);
+    }
+
+    fn get_type(&self) -> String {
+        "recording".to_string()
+    }
+
+    async fn handle_event(&mut self, _event: &str, _payload: &str) -> fabric::Result<()> {
+        Ok(())
+    }
+
+    async fn update_config(&mut self, config: NodeConfig) {
+        self.update_calls
+            .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        self.config = Some(config);
+    }
+
+    fn as_any(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_set_config_replaces_and_update_config_merges() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator = Orchestrator::new(
+        "test_config_split_orchestrator".to_string(),
+        session.clone(),
+    )
+    .await?;
+    let interface = RecordingInterface::default();
+    let set_calls = interface.set_calls.clone();
+    let update_calls = interface.update_calls.clone();
+
+    let node = Arc::new(
+        Node::new(
+            "config_split_node".to_string(),
+            "recording".to_string(),
+            NodeConfig {
+                node_id: "config_split_node".to_string(),
+                config: serde_json::json!({"a": 1, "b": 1}),
+            },
+            session.clone(),
+            Some(Box::new(interface)),
+        )
+        .await?,
+    );
+
+    // Full replacement drops keys that are not in the new config
+    node.set_config(NodeConfig {
+        node_id: "config_split_node".to_string(),
+        config: serde_json::json!({"a": 2}),
+    })
+    .await?;
+    assert_eq!(node.get_config().await.config, serde_json::json!({"a": 2}));
+    assert_eq!(set_calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+    // A patch is merged into the current config
+    node.update_config(NodeConfig {
+        node_id: "config_split_node".to_string(),
+        config: serde_json::json!({"b": 3}),
+    })
+    .await?;
+    assert_eq!(
+        node.get_config().await.config,
+        serde_json::json!({"a": 2, "b": 3})
+    );
+    assert_eq!(update_calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+    // Patches published by the orchestrator take the merge path
+    let cancel = CancellationToken::new();
+    let cancel_clone = cancel.clone();
+    let node_clone = node.clone();
+    let node_handle = tokio::spawn(async move {
+        node_clone.run(cancel_clone).await.unwrap();
+    });
+    wait_for_node_initialization().await;
+
+    orchestrator
+        .update_node_config("config_split_node", serde_json::json!({"a": null, "c": 4}))
+        .await?;
+    wait_for_node_initialization().await;
+
+    assert_eq!(
+        node.get_config().await.config,
+        serde_json::json!({"b": 3, "c": 4})
+    );
+    assert_eq!(set_calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+    assert_eq!(update_calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+
+    cancel.cancel();
+    let _ = tokio::time::timeout(Duration::from_secs(5), node_handle).await;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_offline_grace_period() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator = Orchestrator::with_config(
+        "test_grace_orchestrator".to_string(),
+        session.clone(),
+        OrchestratorConfig {
+            offline_grace_period: Duration::from_secs(2),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    // Seed a node whose last report predates the orchestrator
+    let mut node_state = NodeState::new(NodeData::new("late_node".to_string()));
+    node_state.last_update = std::time::SystemTime::now() - Duration::from_secs(60);
+    orchestrator
+        .nodes
+        .lock()
+        .await
+        .insert("late_node".to_string(), node_state);
+
+    let cancel = CancellationToken::new();
+    let cancel_clone = cancel.clone();
+    let orchestrator_clone = orchestrator.clone();
+    let orchestrator_handle = tokio::spawn(async move {
+        orchestrator_clone.run(cancel_clone).await.unwrap();
+    });
+
+    // Inside the grace window the stale node is left alone
+    sleep(Duration::from_millis(1200)).await;
+    assert_eq!(
+        orchestrator.nodes.lock().await["late_node"]
+            .last_value
+            .status,
+        NodeStatus::Online
+    );
+
+    // Once the window closes the node is judged normally
+    sleep(Duration::from_millis(2000)).await;
+    assert_eq!(
+        orchestrator.nodes.lock().await["late_node"]
+            .last_value
+            .status,
+        NodeStatus::Offline
+    );
+
+    cancel.cancel();
+    let _ = tokio::time::timeout(Duration::from_secs(5), orchestrator_handle).await;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_export_dot_topology() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator =
+        Orchestrator::new("test_dot_orchestrator".to_string(), session.clone()).await?;
+
+    orchestrator
+        .update_node_state(NodeData::from_fields(
+            "dot_quad_1".to_string(),
+            "quadcopter".to_string(),
+            1234567890,
+            None,
+            NodeStatus::Online,
+        ))
+        .await;
+    orchestrator
+        .update_node_state(NodeData::from_fields(
+            "dot_quad_2".to_string(),
+            "quadcopter".to_string(),
+            1234567890,
+            None,
+            NodeStatus::Offline,
+        ))
+        .await;
+
+    let dot = orchestrator.export_dot().await;
+
+    assert!(dot.starts_with("digraph fabric {"));
+    assert!(dot.trim_end().ends_with('}'));
+    assert_eq!(dot.matches('{').count(), dot.matches('}').count());
+    assert!(dot.contains("\"test_dot_orchestrator\" [shape=doublecircle"));
+    assert!(dot.contains("\"test_dot_orchestrator\" -> \"dot_quad_1\";"));
+    assert!(
+        dot.contains("\"dot_quad_1\" [label=\"dot_quad_1\\nquadcopter\\nonline\", color=green];")
+    );
+    assert!(dot.contains("color=red"));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_publish_retained_serves_late_subscribers() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let node = Node::new(
+        "retained_node".to_string(),
+        "generic".to_string(),
+        NodeConfig {
+            node_id: "retained_node".to_string(),
+            config: serde_json::json!({}),
+        },
+        session.clone(),
+        None,
+    )
+    .await?;
+
+    let topic = "node/retained_node/mode";
+    node.publish_retained(topic, b"hovering".to_vec()).await?;
+    node.publish_retained(topic, b"landing".to_vec()).await?;
+
+    // A get issued after the puts still sees the latest value
+    let replies = session.get(topic).res().await?;
+    let reply = tokio::time::timeout(Duration::from_secs(5), replies.recv_async())
+        .await
+        .map_err(|_| FabricError::Other("Timeout waiting for retained value".into()))?
+        .map_err(|e| FabricError::Other(e.to_string()))?;
+    let sample = reply
+        .sample
+        .map_err(|e| FabricError::Other(format!("Error reply: {:?}", e)))?;
+
+    assert_eq!(
+        sample.value.payload.contiguous().to_vec(),
+        b"landing".to_vec()
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_group_config_reaches_members() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator =
+        Orchestrator::new("test_group_orchestrator".to_string(), session.clone()).await?;
+
+    let cancel = CancellationToken::new();
+    let mut nodes = Vec::new();
+    let mut handles = Vec::new();
+    for (id, groups) in [
+        ("group_member_1", vec!["test_group_alpha"]),
+        (
+            "group_member_2",
+            vec!["test_group_alpha", "test_group_beta"],
+        ),
+        ("group_outsider", vec!["test_group_beta"]),
+    ] {
+        let node = Arc::new(
+            Node::new(
+                id.to_string(),
+                "generic".to_string(),
+                NodeConfig {
+                    node_id: id.to_string(),
+                    config: serde_json::json!({"groups": groups, "rate": 1}),
+                },
+                session.clone(),
+                None,
+            )
+            .await?,
+        );
+        let node_clone = node.clone();
+        let cancel_clone = cancel.clone();
+        handles.push(tokio::spawn(async move {
+            node_clone.run(cancel_clone).await.unwrap();
+        }));
+        nodes.push(node);
+    }
+
+    wait_for_node_initialization().await;
+
+    orchestrator
+        .publish_group_config("test_group_alpha", serde_json::json!({"rate": 5}))
+        .await?;
+
+    wait_for_node_initialization().await;
+
+    assert_eq!(nodes[0].get_config().await.config["rate"], 5);
+    assert_eq!(nodes[1].get_config().await.config["rate"], 5);
+    assert_eq!(nodes[2].get_config().await.config["rate"], 1);
+    assert_eq!(
+        nodes[1].get_groups().await,
+        vec!["test_group_alpha", "test_group_beta"]
+    );
+
+    cancel.cancel();
+    for handle in handles {
+        let _ = tokio::time::timeout(Duration::from_secs(5), handle).await;
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_payload_size_and_latency_histograms() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let node = Node::new(
+        "histogram_node".to_string(),
+        "generic".to_string(),
+        NodeConfig {
+            node_id: "histogram_node".to_string(),
+            config: serde_json::json!({}),
+        },
+        session.clone(),
+        None,
+    )
+    .await?;
+
+    let topic = "node/histogram_node/data";
+    node.create_publisher(topic.to_string()).await?;
+    for size in 1..=100 {
+        node.publish(topic, vec![0u8; size * 10]).await?;
+    }
+
+    let sizes = node.payload_size_snapshot();
+    assert_eq!(sizes.count, 100);
+    assert!((490..=510).contains(&sizes.p50));
+    assert!((980..=1001).contains(&sizes.p99));
+    assert!(sizes.min <= sizes.p50 && sizes.p50 <= sizes.p99 && sizes.p99 <= sizes.max);
+
+    // A config stamped 50ms in the past records at least that much apply latency
+    let sent_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+        - 50;
+    node.set_config(NodeConfig {
+        node_id: "histogram_node".to_string(),
+        config: serde_json::json!({"timestamp": sent_at}),
+    })
+    .await?;
+
+    let latency = node.config_latency_snapshot();
+    assert_eq!(latency.count, 1);
+    assert!(latency.p50 >= 50 && latency.p50 < 5000);
+
+    Ok(())
+}
+
+#[derive(Default)]
+struct TickingInterface {
+    config: Option<NodeConfig>,
+    ticks: u64,
+}
+
+#[async_trait]
+impl NodeInterface for TickingInterface {
+    fn get_config(&self) -> NodeConfig {
+        self.config.clone().unwrap()
+    }
+
+    async fn set_config(&mut self, config: NodeConfig) {
+        self.config = Some(config);
+    }
+
+    fn get_type(&self) -> String {
+        "ticking".to_string()
+    }
+
+    async fn handle_event(&mut self, _event: &str, _payload: &str) -> fabric::Result<()> {
+        Ok(())
+    }
+
+    async fn update_config(&mut self, config: NodeConfig) {
+        self.config = Some(config);
+    }
+
+    fn as_any(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    async fn tick(&mut self, _now: std::time::SystemTime) -> fabric::Result<Option<NodeData>> {
+        self.ticks += 1;
+        Ok(Some(NodeData::from_fields(
+            "ticking_node".to_string(),
+            "ticking".to_string(),
+            self.ticks,
+            Some(serde_json::json!({"tick": self.ticks})),
+            NodeStatus::Online,
+        )))
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_interface_tick_publishes_each_interval() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let (tx, mut rx) = mpsc::channel(100);
+    let subscriber = session
+        .declare_subscriber("fabric/ticking_node/data")
+        .callback(move |sample| {
+            let _ = tx.try_send(sample.value.payload.contiguous().to_vec());
+        })
+        .res()
+        .await?;
+
+    let node = Node::new(
+        "ticking_node".to_string(),
+        "ticking".to_string(),
+        NodeConfig {
+            node_id: "ticking_node".to_string(),
+            config: serde_json::json!({"sampling_rate": 0.2}),
+        },
+        session.clone(),
+        Some(Box::new(TickingInterface::default())),
+    )
+    .await?;
+
+    let cancel = CancellationToken::new();
+    let cancel_clone = cancel.clone();
+    let node_handle = tokio::spawn(async move {
+        node.run(cancel_clone).await.unwrap();
+    });
+
+    sleep(Duration::from_millis(1100)).await;
+    cancel.cancel();
+    let _ = tokio::time::timeout(Duration::from_secs(5), node_handle).await;
+    drop(subscriber);
+
+    let mut ticks = Vec::new();
+    while let Ok(payload) = rx.try_recv() {
+        let node_data: NodeData = serde_json::from_slice(&payload)?;
+        ticks.push(node_data.metadata.unwrap()["tick"].as_u64().unwrap());
+    }
+    assert!(
+        ticks.len() >= 4,
+        "expected at least 4 ticks, got {:?}",
+        ticks
+    );
+    assert!(ticks.windows(2).all(|pair| pair[0] < pair[1]));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_status_debounce_suppresses_flapping() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator = Orchestrator::with_config(
+        "test_debounce_orchestrator".to_string(),
+        session.clone(),
+        OrchestratorConfig {
+            status_debounce: Duration::from_secs(2),
+            ..Default::default()
+        },
+    )
+    .await?;
+    let statuses = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let statuses_clone = statuses.clone();
+    orchestrator
+        .register_callback(
+            "flapping_node",
+            Arc::new(Mutex::new(move |node_data: NodeData| {
+                statuses_clone.lock().unwrap().push(node_data.status);
+            })),
+        )
+        .await?;
+
+    let cancel = CancellationToken::new();
+    let cancel_clone = cancel.clone();
+    let orchestrator_clone = orchestrator.clone();
+    let orchestrator_handle = tokio::spawn(async move {
+        orchestrator_clone.run(cancel_clone).await.unwrap();
+    });
+
+    let status_update = |status: NodeStatus| {
+        NodeData::from_fields(
+            "flapping_node".to_string(),
+            "generic".to_string(),
+            0,
+            None,
+            status,
+        )
+    };
+
+    // Flap within the settle window
+    orchestrator
+        .update_node_state(status_update(NodeStatus::Online))
+        .await;
+    orchestrator
+        .update_node_state(status_update(NodeStatus::Offline))
+        .await;
+    sleep(Duration::from_millis(300)).await;
+    orchestrator
+        .update_node_state(status_update(NodeStatus::Online))
+        .await;
+    orchestrator
+        .update_node_state(status_update(NodeStatus::Offline))
+        .await;
+
+    sleep(Duration::from_millis(1200)).await;
+    assert!(!statuses.lock().unwrap().contains(&NodeStatus::Offline));
+
+    // Once the node has been offline for the whole window the transition is reported
+    sleep(Duration::from_millis(2000)).await;
+    assert_eq!(
+        *statuses.lock().unwrap(),
+        vec![NodeStatus::Online, NodeStatus::Online, NodeStatus::Offline]
+    );
+
+    cancel.cancel();
+    let _ = tokio::time::timeout(Duration::from_secs(5), orchestrator_handle).await;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_apply_and_verify_configs() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator =
+        Orchestrator::new("test_verify_orchestrator".to_string(), session.clone()).await?;
+
+    let cancel = CancellationToken::new();
+    let mut handles = Vec::new();
+    for id in ["verify_node_1", "verify_node_2"] {
+        let node = Node::new(
+            id.to_string(),
+            "generic".to_string(),
+            NodeConfig {
+                node_id: id.to_string(),
+                config: serde_json::json!({}),
+            },
+            session.clone(),
+            None,
+        )
+        .await?;
+        let cancel_clone = cancel.clone();
+        handles.push(tokio::spawn(async move {
+            node.run(cancel_clone).await.unwrap();
+        }));
+    }
+
+    wait_for_node_initialization().await;
+
+    let configs = ["verify_node_1", "verify_node_2", "verify_node_missing"]
+        .iter()
+        .enumerate()
+        .map(|(i, id)| {
+            (
+                id.to_string(),
+                NodeConfig {
+                    node_id: id.to_string(),
+                    config: serde_json::json!({"threshold": i}),
+                },
+            )
+        })
+        .collect();
+
+    let failed = orchestrator
+        .apply_and_verify(configs, Duration::from_secs(2))
+        .await?;
+
+    assert_eq!(failed, vec!["verify_node_missing".to_string()]);
+
+    cancel.cancel();
+    for handle in handles {
+        let _ = tokio::time::timeout(Duration::from_secs(5), handle).await;
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_oversized_payload_dropped() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator =
+        Orchestrator::new("test_oversized_orchestrator".to_string(), session.clone()).await?;
+
+    let (tx, mut rx) = mpsc::channel(10);
+    orchestrator
+        .create_subscriber_with_options(
+            "test_oversized_topic".to_string(),
+            Arc::new(Mutex::new(move |sample: Sample| {
+                let _ = tx.try_send(sample.value.payload.contiguous().len());
+            })),
+            SubscriberOptions {
+                max_payload_bytes: 1024,
+                ..Default::default()
+            },
+        )
+        .await?;
+
+    session
+        .put("test_oversized_topic", vec![0u8; 4096])
+        .res()
+        .await?;
+    session
+        .put("test_oversized_topic", vec![0u8; 16])
+        .res()
+        .await?;
+
+    let received = tokio::time::timeout(Duration::from_secs(2), rx.recv())
+        .await
+        .expect("normal sample not delivered");
+    assert_eq!(received, Some(16));
+    sleep(Duration::from_millis(200)).await;
+    assert!(rx.try_recv().is_err());
+    assert_eq!(orchestrator.metrics().await.oversized_dropped, 1);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_payload_limit_applies_to_node_and_orchestrator_ingest() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator = Orchestrator::with_config(
+        "test_payload_limit_orchestrator".to_string(),
+        session.clone(),
+        OrchestratorConfig {
+            max_payload_bytes: 1024,
+            ..Default::default()
+        },
+    )
+    .await?;
+    let node = Node::with_options(
+        "payload_limit_node".to_string(),
+        "generic".to_string(),
+        NodeConfig {
+            node_id: "payload_limit_node".to_string(),
+            config: serde_json::json!({ "rate": 1 }),
+        },
+        session.clone(),
+        None,
+        NodeOptions {
+            max_payload_bytes: Some(1024),
+            ..Default::default()
+        },
+    )
+    .await?;
+    let cancel = CancellationToken::new();
+    let node_clone = node.clone();
+    let node_cancel = cancel.clone();
+    let node_handle = tokio::spawn(async move { node_clone.run(node_cancel).await });
+    let orchestrator_clone = orchestrator.clone();
+    let orchestrator_cancel = cancel.clone();
+    let orchestrator_handle =
+        tokio::spawn(async move { orchestrator_clone.run(orchestrator_cancel).await });
+    wait_for_node_initialization().await;
+
+    // An oversized config never reaches the node's config
+    let oversized = NodeConfig {
+        node_id: "payload_limit_node".to_string(),
+        config: serde_json::json!({ "rate": 2, "padding": "x".repeat(4096) }),
+    };
+    session
+        .put(
+            "fabric/payload_limit_node/config",
+            serde_json::to_string(&oversized)?,
+        )
+        .res()
+        .await?;
+    sleep(Duration::from_millis(300)).await;
+    assert_eq!(node.get_config().await.config["rate"], 1);
+    assert_eq!(node.oversized_dropped(), 1);
+
+    // Nor does an oversized status reach the orchestrator's node table
+    let mut node_data = NodeData::new("oversized_status_node".to_string());
+    node_data.metadata = Some(serde_json::json!({ "padding": "x".repeat(4096) }));
+    session
+        .put("fabric/oversized_status_node/status", node_data.to_json()?)
+        .res()
+        .await?;
+    sleep(Duration::from_millis(300)).await;
+    let nodes = orchestrator.get_nodes().await;
+    assert!(nodes.contains_key("payload_limit_node"));
+    assert!(!nodes.contains_key("oversized_status_node"));
+
+    cancel.cancel();
+    let _ = tokio::time::timeout(Duration::from_secs(5), node_handle).await;
+    let _ = tokio::time::timeout(Duration::from_secs(5), orchestrator_handle).await;
+    Ok(())
+}
+
+struct ModalInterface {
+    config: NodeConfig,
+    mode: StateMachine,
+}
+
+#[async_trait]
+impl NodeInterface for ModalInterface {
+    fn get_config(&self) -> NodeConfig {
+        self.config.clone()
+    }
+
+    async fn set_config(&mut self, config: NodeConfig) {
+        self.config = config;
+    }
+
+    fn get_type(&self) -> String {
+        "modal".to_string()
+    }
+
+    async fn handle_event(&mut self, _event: &str, _payload: &str) -> fabric::Result<()> {
+        Ok(())
+    }
+
+    async fn update_config(&mut self, config: NodeConfig) {
+        self.config = config;
+    }
+
+    fn as_any(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn state_machine(&self) -> Option<StateMachine> {
+        Some(self.mode.clone())
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_node_mode_transitions() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let config = NodeConfig {
+        node_id: "modal_node".to_string(),
+        config: serde_json::json!({}),
+    };
+    let mode = StateMachine::new("idle")
+        .allow("idle", &["flying"])
+        .allow("flying", &["landing"])
+        .allow("landing", &["idle"]);
+    let node = Node::new(
+        "modal_node".to_string(),
+        "modal".to_string(),
+        config.clone(),
+        session,
+        Some(Box::new(ModalInterface {
+            config,
+            mode: mode.clone(),
+        })),
+    )
+    .await?;
+
+    assert_eq!(node.mode().as_deref(), Some("idle"));
+
+    // Landing is only reachable while flying
+    assert!(matches!(
+        node.transition("landing"),
+        Err(FabricError::InvalidTransition(from, to)) if from == "idle" && to == "landing"
+    ));
+    assert_eq!(node.mode().as_deref(), Some("idle"));
+
+    node.transition("flying")?;
+    assert_eq!(node.mode().as_deref(), Some("flying"));
+
+    // The interface's machine is the node's, whichever side transitions
+    assert_eq!(mode.current(), "flying");
+    mode.transition("landing")?;
+    assert_eq!(node.mode().as_deref(), Some("landing"));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_health_check_times_out_unresponsive_node() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator = Orchestrator::with_config(
+        "test_health_timeout_orchestrator".to_string(),
+        session.clone(),
+        OrchestratorConfig {
+            health_query_timeout: Duration::from_millis(300),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    // Accept status queries but never answer them
+    let held_queries = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let held_queries_clone = held_queries.clone();
+    let _queryable = session
+        .declare_queryable("fabric/silent_node/status")
+        .callback(move |query| held_queries_clone.lock().unwrap().push(query))
+        .res()
+        .await?;
+
+    orchestrator
+        .update_node_state(NodeData::from_fields(
+            "silent_node".to_string(),
+            "generic".to_string(),
+            0,
+            None,
+            NodeStatus::Online,
+        ))
+        .await;
+
+    let sweep = tokio::spawn({
+        let orchestrator = orchestrator.clone();
+        async move { orchestrator.check_node_health().await }
+    });
+    // The node table stays available while the sweep waits on the silent node
+    sleep(Duration::from_millis(100)).await;
+    tokio::time::timeout(Duration::from_millis(100), orchestrator.get_nodes())
+        .await
+        .expect("health sweep held the node table while querying");
+    tokio::time::timeout(Duration::from_secs(3), sweep)
+        .await
+        .expect("health sweep stalled on unresponsive node")
+        .unwrap();
+
+    let nodes = orchestrator.get_nodes().await;
+    assert_eq!(nodes["silent_node"].last_value.status, NodeStatus::Unknown);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_health_check_handles_malformed_and_cbor_replies() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator = Orchestrator::with_config(
+        "test_health_replies_orchestrator".to_string(),
+        session.clone(),
+        OrchestratorConfig {
+            health_query_timeout: Duration::from_millis(300),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    let _malformed_queryable = session
+        .declare_queryable("fabric/malformed_status_node/status")
+        .callback(|query| {
+            let key_expr = query.key_expr().clone();
+            tokio::spawn(async move {
+                let _ = query
+                    .reply(Ok(Sample::new(key_expr, "not a status")))
+                    .res()
+                    .await;
+            });
+        })
+        .res()
+        .await?;
+    let cbor_status = PayloadEncoding::Cbor.encode(&NodeData::from_fields(
+        "cbor_status_node".to_string(),
+        "generic".to_string(),
+        0,
+        None,
+        NodeStatus::Online,
+    ))?;
+    let _cbor_queryable = session
+        .declare_queryable("fabric/cbor_status_node/status")
+        .callback(move |query| {
+            let key_expr = query.key_expr().clone();
+            let value =
+                Value::from(cbor_status.clone()).encoding(PayloadEncoding::Cbor.zenoh_encoding());
+            tokio::spawn(async move {
+                let _ = query.reply(Ok(Sample::new(key_expr, value))).res().await;
+            });
+        })
+        .res()
+        .await?;
+
+    for node_id in ["malformed_status_node", "cbor_status_node"] {
+        orchestrator
+            .update_node_state(NodeData::from_fields(
+                node_id.to_string(),
+                "generic".to_string(),
+                0,
+                None,
+                NodeStatus::Online,
+            ))
+            .await;
+    }
+
+    tokio::time::timeout(Duration::from_secs(5), orchestrator.check_node_health())
+        .await
+        .expect("health sweep stalled");
+
+    let nodes = orchestrator.get_nodes().await;
+    assert_eq!(
+        nodes["malformed_status_node"].last_value.status,
+        NodeStatus::Unknown
+    );
+    assert_eq!(
+        nodes["cbor_status_node"].last_value.status,
+        NodeStatus::Online
+    );
+
+    Ok(())
+}
+
+#[cfg(feature = "protobuf")]
+#[test]
+fn test_node_data_protobuf_round_trip() -> fabric::Result<()> {
+    let node_data = NodeData::from_fields(
+        "proto_node".to_string(),
+        "sensor".to_string(),
+        1_700_000_000,
+        Some(serde_json::json!({"temperature": 21.5, "tags": ["a", "b"]})),
+        NodeStatus::Online,
+    );
+
+    let bytes = node_data.to_protobuf()?;
+    assert_eq!(NodeData::from_protobuf(&bytes)?, node_data);
+
+    let without_metadata = NodeData::from_fields(
+        "proto_node".to_string(),
+        "sensor".to_string(),
+        0,
+        None,
+        NodeStatus::Offline,
+    );
+    let bytes = without_metadata.to_protobuf()?;
+    assert_eq!(NodeData::from_protobuf(&bytes)?, without_metadata);
+
+    Ok(())
+}
+
+#[test]
+fn test_node_data_rfc3339_timestamps_round_trip() -> fabric::Result<()> {
+    let node_data = NodeData::from_fields(
+        "rfc3339_node".to_string(),
+        "sensor".to_string(),
+        1_700_000_000,
+        None,
+        NodeStatus::Online,
+    );
+
+    // Epoch stays the default wire form
+    assert_eq!(
+        node_data.to_value(TimestampFormat::default())?["timestamp"],
+        serde_json::json!(1_700_000_000)
+    );
+
+    let json = node_data.encode(PayloadEncoding::Json, TimestampFormat::Rfc3339)?;
+    let value: serde_json::Value = serde_json::from_slice(&json)?;
+    assert_eq!(value["timestamp"], "2023-11-14T22:13:20Z");
+    assert_eq!(
+        NodeData::from_json(std::str::from_utf8(&json).unwrap())?,
+        node_data
+    );
+
+    let cbor = node_data.encode(PayloadEncoding::Cbor, TimestampFormat::Rfc3339)?;
+    let decoded: NodeData = PayloadEncoding::Cbor.decode(&cbor.into())?;
+    assert_eq!(decoded, node_data);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_pause_and_resume_ingestion() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator =
+        Orchestrator::new("test_pause_orchestrator".to_string(), session.clone()).await?;
+
+    let processed = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let processed_clone = processed.clone();
+    orchestrator
+        .create_subscriber(
+            "test_pause_topic".to_string(),
+            Arc::new(Mutex::new(move |_sample: Sample| {
+                processed_clone.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            })),
+        )
+        .await?;
+
+    orchestrator.pause();
+    assert!(orchestrator.is_paused());
+    for i in 0..5 {
+        session
+            .put("test_pause_topic", format!("sample {}", i))
+            .res()
+            .await?;
+    }
+    sleep(Duration::from_millis(300)).await;
+
+    assert_eq!(processed.load(std::sync::atomic::Ordering::SeqCst), 0);
+    assert_eq!(orchestrator.metrics().await.paused_dropped, 5);
+
+    orchestrator.resume();
+    session
+        .put("test_pause_topic", "after resume")
+        .res()
+        .await?;
+    sleep(Duration::from_millis(300)).await;
+
+    assert_eq!(processed.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_reconcile_restores_drifted_config() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let desired = NodeConfig {
+        node_id: "reconcile_node".to_string(),
+        config: serde_json::json!({"rate": 10}),
+    };
+    let orchestrator = Orchestrator::with_config(
+        "test_reconcile_orchestrator".to_string(),
+        session.clone(),
+        OrchestratorConfig {
+            nodes: vec![desired.clone()],
+            reconcile_interval: Some(Duration::from_millis(300)),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    let node = Node::new(
+        "reconcile_node".to_string(),
+        "generic".to_string(),
+        desired.clone(),
+        session.clone(),
+        None,
+    )
+    .await?;
+
+    let cancel = CancellationToken::new();
+    let node_clone = node.clone();
+    let node_cancel = cancel.clone();
+    let node_handle = tokio::spawn(async move { node_clone.run(node_cancel).await });
+    let orchestrator_clone = orchestrator.clone();
+    let orchestrator_cancel = cancel.clone();
+    let orchestrator_handle =
+        tokio::spawn(async move { orchestrator_clone.run(orchestrator_cancel).await });
+
+    wait_for_node_initialization().await;
+
+    // Change the node's config behind the orchestrator's back
+    let drifted = NodeConfig {
+        node_id: "reconcile_node".to_string(),
+        config: serde_json::json!({"rate": 99}),
+    };
+    session
+        .put(
+            "fabric/reconcile_node/config",
+            serde_json::to_string(&drifted)?,
+        )
+        .res()
+        .await?;
+
+    let mut drift_applied = false;
+    for _ in 0..20 {
+        sleep(Duration::from_millis(10)).await;
+        if node.get_config().await == drifted {
+            drift_applied = true;
+            break;
+        }
+    }
+    assert!(
+        drift_applied,
+        "external config change never reached the node"
+    );
+
+    let mut restored = false;
+    for _ in 0..30 {
+        sleep(Duration::from_millis(100)).await;
+        if node.get_config().await == desired {
+            restored = true;
+            break;
+        }
+    }
+    assert!(restored, "reconcile loop did not restore desired config");
+
+    cancel.cancel();
+    let _ = tokio::time::timeout(Duration::from_secs(5), node_handle).await;
+    let _ = tokio::time::timeout(Duration::from_secs(5), orchestrator_handle).await;
+
+    Ok(())
+}
+
+struct EventInterface {
+    config: NodeConfig,
+    events: mpsc::Sender<(String, String)>,
+}
+
+#[async_trait]
+impl NodeInterface for EventInterface {
+    fn get_config(&self) -> NodeConfig {
+        self.config.clone()
+    }
+
+    async fn set_config(&mut self, config: NodeConfig) {
+        self.config = config;
+    }
+
+    fn get_type(&self) -> String {
+        "event".to_string()
+    }
+
+    async fn handle_event(&mut self, event: &str, payload: &str) -> fabric::Result<()> {
+        let _ = self
+            .events
+            .send((event.to_string(), payload.to_string()))
+            .await;
+        Ok(())
+    }
+
+    async fn update_config(&mut self, config: NodeConfig) {
+        self.config = config;
+    }
+
+    fn as_any(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_broadcast_reaches_running_node() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator =
+        Orchestrator::new("test_broadcast_orchestrator".to_string(), session.clone()).await?;
+
+    let (tx, mut rx) = mpsc::channel(10);
+    let config = NodeConfig {
+        node_id: "broadcast_node".to_string(),
+        config: serde_json::json!({}),
+    };
+    let node = Node::new(
+        "broadcast_node".to_string(),
+        "event".to_string(),
+        config.clone(),
+        session.clone(),
+        Some(Box::new(EventInterface { config, events: tx })),
+    )
+    .await?;
+
+    let cancel = CancellationToken::new();
+    let cancel_clone = cancel.clone();
+    let node_handle = tokio::spawn(async move { node.run(cancel_clone).await });
+
+    wait_for_node_initialization().await;
+
+    orchestrator
+        .broadcast("emergency_stop", serde_json::json!("all motors"))
+        .await?;
+
+    let (event, payload) = tokio::time::timeout(Duration::from_secs(2), rx.recv())
+        .await
+        .expect("broadcast not delivered")
+        .unwrap();
+    assert_eq!(event, "emergency_stop");
+    assert_eq!(payload, "all motors");
+
+    cancel.cancel();
+    let _ = tokio::time::timeout(Duration::from_secs(5), node_handle).await;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_seeded_nodes_replay_identical_telemetry() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let mut sequences = Vec::new();
+    for id in ["seeded_node_1", "seeded_node_2"] {
+        let node = Node::with_options(
+            id.to_string(),
+            "generic".to_string(),
+            NodeConfig {
+                node_id: id.to_string(),
+                config: serde_json::json!({}),
+            },
+            session.clone(),
+            None,
+            NodeOptions {
+                seed: Some(42),
+                ..Default::default()
+            },
+        )
+        .await?;
+        let telemetry: Vec<f64> = (0..10)
+            .map(|_| node.with_rng(|rng| rng.gen_range(-1.0..1.0)))
+            .collect();
+        sequences.push(telemetry);
+    }
+
+    assert_eq!(sequences[0], sequences[1]);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_queued_command_delivered_on_reconnect() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator =
+        Orchestrator::new("test_command_orchestrator".to_string(), session.clone()).await?;
+    let cancel = CancellationToken::new();
+    let orchestrator_clone = orchestrator.clone();
+    let orchestrator_cancel = cancel.clone();
+    let orchestrator_handle =
+        tokio::spawn(async move { orchestrator_clone.run(orchestrator_cancel).await });
+
+    sleep(Duration::from_millis(200)).await;
+
+    // The node has not come online yet, so both commands wait in the queue
+    orchestrator
+        .send_command("command_node", "take_off", serde_json::json!("10m"))
+        .await?;
+    orchestrator
+        .send_command("command_node", "land", serde_json::Value::Null)
+        .await?;
+    assert_eq!(orchestrator.queued_commands("command_node").await, 2);
+
+    let (tx, mut rx) = mpsc::channel(10);
+    let config = NodeConfig {
+        node_id: "command_node".to_string(),
+        config: serde_json::json!({}),
+    };
+    let node = Node::new(
+        "command_node".to_string(),
+        "event".to_string(),
+        config.clone(),
+        session.clone(),
+        Some(Box::new(EventInterface { config, events: tx })),
+    )
+    .await?;
+    let node_cancel = cancel.clone();
+    let node_handle = tokio::spawn(async move { node.run(node_cancel).await });
+
+    let mut received = Vec::new();
+    for _ in 0..2 {
+        let event = tokio::time::timeout(Duration::from_secs(3), rx.recv())
+            .await
+            .expect("queued command not delivered")
+            .unwrap();
+        received.push(event);
+    }
+    assert_eq!(
+        received,
+        vec![
+            ("take_off".to_string(), "10m".to_string()),
+            ("land".to_string(), String::new()),
+        ]
+    );
+    assert_eq!(orchestrator.queued_commands("command_node").await, 0);
+
+    cancel.cancel();
+    let _ = tokio::time::timeout(Duration::from_secs(5), node_handle).await;
+    let _ = tokio::time::timeout(Duration::from_secs(5), orchestrator_handle).await;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_subscribe_to_node_delivers_snapshot() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator =
+        Orchestrator::new("test_snapshot_orchestrator".to_string(), session.clone()).await?;
+
+    let known = NodeData::from_fields(
+        "snapshot_node".to_string(),
+        "generic".to_string(),
+        7,
+        Some(serde_json::json!({"battery": 80})),
+        NodeStatus::Online,
+    );
+    orchestrator.update_node_state(known.clone()).await;
+
+    let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let received_clone = received.clone();
+    orchestrator
+        .subscribe_to_node(
+            "snapshot_node",
+            Arc::new(Mutex::new(move |node_data: NodeData| {
+                received_clone.lock().unwrap().push(node_data);
+            })),
+        )
+        .await?;
+    assert_eq!(received.lock().unwrap().as_slice(), &[known]);
+
+    // Unknown nodes get no snapshot
+    let unknown_calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let unknown_calls_clone = unknown_calls.clone();
+    orchestrator
+        .subscribe_to_node(
+            "snapshot_unknown_node",
+            Arc::new(Mutex::new(move |_node_data: NodeData| {
+                unknown_calls_clone.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            })),
+        )
+        .await?;
+    assert_eq!(unknown_calls.load(std::sync::atomic::Ordering::SeqCst), 0);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_express_publisher_delivers() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let node = Node::new(
+        "express_node".to_string(),
+        "generic".to_string(),
+        NodeConfig {
+            node_id: "express_node".to_string(),
+            config: serde_json::json!({}),
+        },
+        session.clone(),
+        None,
+    )
+    .await?;
+
+    let subscriber = session
+        .declare_subscriber("test_express_topic")
+        .res()
+        .await?;
+
+    node.create_publisher_with_options(
+        "test_express_topic".to_string(),
+        PublisherOptions {
+            express: true,
+            ..Default::default()
+        },
+    )
+    .await?;
+    node.publish("test_express_topic", b"stop".to_vec()).await?;
+
+    let sample = tokio::time::timeout(Duration::from_secs(2), subscriber.recv_async())
+        .await
+        .expect("express sample not delivered")
+        .unwrap();
+    assert_eq!(sample.value.payload.contiguous().as_ref(), b"stop");
+
+    // Express for a single sample on an ordinary publisher
+    node.create_publisher("test_express_call_topic".to_string())
+        .await?;
+    let call_subscriber = session
+        .declare_subscriber("test_express_call_topic")
+        .res()
+        .await?;
+    let express = PublisherOptions {
+        express: true,
+        ..Default::default()
+    };
+    node.publish_with_options("test_express_call_topic", b"halt".to_vec(), &express)
+        .await?;
+
+    let sample = tokio::time::timeout(Duration::from_secs(2), call_subscriber.recv_async())
+        .await
+        .expect("per-call express sample not delivered")
+        .unwrap();
+    assert_eq!(sample.value.payload.contiguous().as_ref(), b"halt");
+    assert!(node
+        .publish_with_options("no_such_topic", Vec::new(), &express)
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+struct FailingSelfTestInterface {
+    config: NodeConfig,
+}
+
+#[async_trait]
+impl NodeInterface for FailingSelfTestInterface {
+    fn get_config(&self) -> NodeConfig {
+        self.config.clone()
+    }
+
+    async fn set_config(&mut self, config: NodeConfig) {
+        self.config = config;
+    }
+
+    fn get_type(&self) -> String {
+        "failing".to_string()
+    }
+
+    async fn handle_event(&mut self, _event: &str, _payload: &str) -> fabric::Result<()> {
+        Ok(())
+    }
+
+    async fn update_config(&mut self, config: NodeConfig) {
+        self.config = config;
+    }
+
+    fn as_any(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    async fn self_test(&self) -> fabric::Result<()> {
+        Err(FabricError::Other("gyro not responding".to_string()))
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_failed_self_test_reports_failed() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let subscriber = session
+        .declare_subscriber("fabric/self_test_node/status")
+        .res()
+        .await?;
+
+    let config = NodeConfig {
+        node_id: "self_test_node".to_string(),
+        config: serde_json::json!({}),
+    };
+    let node = Node::new(
+        "self_test_node".to_string(),
+        "failing".to_string(),
+        config.clone(),
+        session.clone(),
+        Some(Box::new(FailingSelfTestInterface { config })),
+    )
+    .await?;
+
+    // Fail-fast: run returns without entering the main loop
+    let result = tokio::time::timeout(Duration::from_secs(2), node.run(CancellationToken::new()))
+        .await
+        .expect("node entered its main loop despite failing self-test");
+    assert!(matches!(result, Err(FabricError::SelfTestFailed(_))));
+
+    let sample = tokio::time::timeout(Duration::from_secs(2), subscriber.recv_async())
+        .await
+        .expect("no status published")
+        .unwrap();
+    let status: NodeData = serde_json::from_slice(&sample.value.payload.contiguous())?;
+    assert_eq!(status.status, NodeStatus::Failed);
+    assert!(status.metadata.unwrap()["error"]
+        .as_str()
+        .unwrap()
+        .contains("gyro not responding"));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_find_nodes_by_metadata() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator =
+        Orchestrator::new("test_find_orchestrator".to_string(), session.clone()).await?;
+
+    for (node_id, metadata) in [
+        ("quad_low", Some(serde_json::json!({"battery": 12.5}))),
+        ("quad_full", Some(serde_json::json!({"battery": 95}))),
+        ("quad_empty", Some(serde_json::json!({"battery": 0}))),
+        ("quad_silent", None),
+    ] {
+        orchestrator
+            .update_node_state(NodeData::from_fields(
+                node_id.to_string(),
+                "quadcopter".to_string(),
+                0,
+                metadata,
+                NodeStatus::Online,
+            ))
+            .await;
+    }
+
+    assert_eq!(
+        orchestrator.find_by_metadata_lt("battery", 20.0).await,
+        vec!["quad_empty".to_string(), "quad_low".to_string()]
+    );
+    assert_eq!(
+        orchestrator
+            .find_nodes(|node_data| node_data.metadata.is_none())
+            .await,
+        vec!["quad_silent".to_string()]
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_list_nodes_by_status_and_type() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator =
+        Orchestrator::new("test_list_orchestrator".to_string(), session.clone()).await?;
+
+    for (node_id, node_type, status) in [
+        ("list_quad_a", "quadcopter", NodeStatus::Online),
+        ("list_quad_b", "quadcopter", NodeStatus::Offline),
+        ("list_rover_a", "rover", NodeStatus::Offline),
+        ("list_rover_b", "rover", NodeStatus::Degraded),
+    ] {
+        orchestrator
+            .update_node_state(NodeData::from_fields(
+                node_id.to_string(),
+                node_type.to_string(),
+                0,
+                None,
+                status,
+            ))
+            .await;
+    }
+
+    assert_eq!(
+        orchestrator.list_nodes_by_status(NodeStatus::Offline).await,
+        vec!["list_quad_b".to_string(), "list_rover_a".to_string()]
+    );
+    assert_eq!(
+        orchestrator.list_nodes_by_status(NodeStatus::Online).await,
+        vec!["list_quad_a".to_string()]
+    );
+    assert_eq!(
+        orchestrator.list_nodes_by_type("rover").await,
+        vec!["list_rover_a".to_string(), "list_rover_b".to_string()]
+    );
+    assert!(orchestrator.list_nodes_by_type("boat").await.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_select_nodes_by_label() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator =
+        Orchestrator::new("test_select_orchestrator".to_string(), session.clone()).await?;
+
+    let cancel = CancellationToken::new();
+    let orchestrator_clone = orchestrator.clone();
+    let orchestrator_cancel = cancel.clone();
+    let mut handles = vec![tokio::spawn(async move {
+        orchestrator_clone.run(orchestrator_cancel).await
+    })];
+    for (node_id, labels) in [
+        ("select_prod_gpu", vec![("env", "prod"), ("gpu", "a100")]),
+        ("select_prod", vec![("env", "prod")]),
+        ("select_staging", vec![("env", "staging"), ("gpu", "t4")]),
+    ] {
+        let node = Node::with_options(
+            node_id.to_string(),
+            "generic".to_string(),
+            NodeConfig {
+                node_id: node_id.to_string(),
+                config: serde_json::json!({}),
+            },
+            session.clone(),
+            None,
+            NodeOptions {
+                labels: labels
+                    .into_iter()
+                    .map(|(key, value)| (key.to_string(), value.to_string()))
+                    .collect(),
+                ..Default::default()
+            },
+        )
+        .await?;
+        let node_cancel = cancel.clone();
+        handles.push(tokio::spawn(async move { node.run(node_cancel).await }));
+    }
+
+    // Wait for all three nodes, not just the ones the first selector matches
+    for _ in 0..50 {
+        if orchestrator.select("env").await.len() == 3 {
+            break;
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    assert_eq!(
+        orchestrator.select("env=prod").await,
+        vec!["select_prod".to_string(), "select_prod_gpu".to_string()]
+    );
+    assert_eq!(
+        orchestrator.select("env=prod, gpu").await,
+        vec!["select_prod_gpu".to_string()]
+    );
+    assert_eq!(
+        orchestrator.select("gpu").await,
+        vec!["select_prod_gpu".to_string(), "select_staging".to_string()]
+    );
+    assert!(orchestrator.select("env=dev").await.is_empty());
+
+    cancel.cancel();
+    for handle in handles {
+        let _ = tokio::time::timeout(Duration::from_secs(5), handle).await;
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_publish_auto_declares_publisher() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let node = Node::new(
+        "auto_publish_node".to_string(),
+        "generic".to_string(),
+        NodeConfig {
+            node_id: "auto_publish_node".to_string(),
+            config: serde_json::json!({}),
+        },
+        session.clone(),
+        None,
+    )
+    .await?;
+
+    let subscriber = session
+        .declare_subscriber("test_auto_publish_topic")
+        .res()
+        .await?;
+
+    // The strict variant still requires a declared publisher
+    assert!(node
+        .publish("test_auto_publish_topic", b"strict".to_vec())
+        .await
+        .is_err());
+    assert!(!node.has_publisher("test_auto_publish_topic").await);
+
+    node.publish_auto("test_auto_publish_topic", b"first".to_vec())
+        .await?;
+    assert!(node.has_publisher("test_auto_publish_topic").await);
+    node.publish_auto("test_auto_publish_topic", b"second".to_vec())
+        .await?;
+
+    for expected in [b"first".as_slice(), b"second".as_slice()] {
+        let sample = tokio::time::timeout(Duration::from_secs(2), subscriber.recv_async())
+            .await
+            .expect("auto-published sample not delivered")
+            .unwrap();
+        assert_eq!(sample.value.payload.contiguous().as_ref(), expected);
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_config_changes_are_audited() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let initial = NodeConfig {
+        node_id: "audit_node".to_string(),
+        config: serde_json::json!({"rate": 1}),
+    };
+    let node = Node::with_options(
+        "audit_node".to_string(),
+        "generic".to_string(),
+        initial.clone(),
+        session.clone(),
+        None,
+        NodeOptions {
+            publish_audit: true,
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    let audit_subscriber = session
+        .declare_subscriber("fabric/audit_node/audit")
+        .res()
+        .await?;
+
+    let replaced = NodeConfig {
+        node_id: "audit_node".to_string(),
+        config: serde_json::json!({"rate": 2}),
+    };
+    node.set_config(replaced.clone()).await?;
+    node.update_config(NodeConfig {
+        node_id: "audit_node".to_string(),
+        config: serde_json::json!({"mode": "fast"}),
+    })
+    .await?;
+
+    let entries = node.config_audit();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].old_hash, config_hash(&initial));
+    assert_eq!(entries[0].new_hash, config_hash(&replaced));
+    assert_eq!(entries[1].old_hash, config_hash(&replaced));
+    assert_eq!(entries[1].new_hash, config_hash(&node.get_config().await));
+    assert!(entries.iter().all(|entry| entry.source == "local"));
+
+    let sample = tokio::time::timeout(Duration::from_secs(2), audit_subscriber.recv_async())
+        .await
+        .expect("audit entry not published")
+        .unwrap();
+    let published: AuditEntry = serde_json::from_slice(&sample.value.payload.contiguous())?;
+    assert_eq!(published, entries[0]);
+
+    Ok(())
+}
+
+struct FloatInterface {
+    config: NodeConfig,
+}
+
+#[async_trait]
+impl NodeInterface for FloatInterface {
+    fn get_config(&self) -> NodeConfig {
+        self.config.clone()
+    }
+
+    async fn set_config(&mut self, config: NodeConfig) {
+        self.config = config;
+    }
+
+    fn get_type(&self) -> String {
+        "float".to_string()
+    }
+
+    async fn handle_event(&mut self, _event: &str, _payload: &str) -> fabric::Result<()> {
+        Ok(())
+    }
+
+    async fn update_config(&mut self, config: NodeConfig) {
+        self.config = config;
+    }
+
+    fn as_any(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn metrics(&self) -> serde_json::Value {
+        serde_json::json!({"temperature": 21.456})
+    }
+
+    async fn tick(&mut self, _now: std::time::SystemTime) -> fabric::Result<Option<NodeData>> {
+        Ok(Some(NodeData::from_fields(
+            "rounding_node".to_string(),
+            "float".to_string(),
+            1,
+            Some(serde_json::json!({"voltage": 4.98765})),
+            NodeStatus::Online,
+        )))
+    }
+}
+
+async fn recv_payload(receiver: &flume::Receiver<Sample>) -> String {
+    let sample = tokio::time::timeout(Duration::from_secs(2), receiver.recv_async())
+        .await
+        .expect("sample not delivered")
+        .unwrap();
+    String::from_utf8(sample.value.payload.contiguous().to_vec()).unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_published_floats_rounded_to_significant_digits() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let config = NodeConfig {
+        node_id: "rounding_node".to_string(),
+        config: serde_json::json!({}),
+    };
+    let node = Node::with_options(
+        "rounding_node".to_string(),
+        "float".to_string(),
+        config.clone(),
+        session.clone(),
+        Some(Box::new(FloatInterface { config })),
+        NodeOptions {
+            float_significant_digits: Some(3),
+            ..Default::default()
+        },
+    )
+    .await?;
+    let subscriber = session
+        .declare_subscriber("test_rounding_topic")
+        .res()
+        .await?;
+    node.create_publisher("test_rounding_topic".to_string())
+        .await?;
+    node.publish_json(
+        "test_rounding_topic",
+        serde_json::json!({
+            "value": 0.1 + 0.2,
+            "readings": [123.456, -0.0012345],
+            "count": 12345,
+        }),
+    )
+    .await?;
+
+    let payload = recv_payload(&subscriber).await;
+    assert!(payload.contains("\"value\":0.3"), "{}", payload);
+    let node_data: NodeData = serde_json::from_str(&payload)?;
+    assert_eq!(
+        node_data.metadata.unwrap(),
+        serde_json::json!({
+            "value": 0.3,
+            "readings": [123.0, -0.00123],
+            "count": 12345,
+            "temperature": 21.5,
+        })
+    );
+
+    // Status metadata comes from the interface's metrics
+    let status_subscriber = session
+        .declare_subscriber("fabric/rounding_node/status")
+        .res()
+        .await?;
+    node.update_status(NodeStatus::Online).await?;
+    let node_data: NodeData = serde_json::from_str(&recv_payload(&status_subscriber).await)?;
+    assert_eq!(
+        node_data.metadata.unwrap(),
+        serde_json::json!({"temperature": 21.5})
+    );
+
+    // Tick data is whatever the interface returns
+    let data_subscriber = session
+        .declare_subscriber("fabric/rounding_node/data")
+        .res()
+        .await?;
+    node.tick().await?;
+    let node_data: NodeData = serde_json::from_str(&recv_payload(&data_subscriber).await)?;
+    assert_eq!(
+        node_data.metadata.unwrap(),
+        serde_json::json!({"voltage": 4.99})
+    );
+
+    let negotiated_subscriber = session
+        .declare_subscriber("test_negotiated_rounding_topic")
+        .res()
+        .await?;
+    node.publish_negotiated(
+        "test_negotiated_rounding_topic",
+        &serde_json::json!({"speed": 9.87654}),
+    )
+    .await?;
+    let value: serde_json::Value =
+        serde_json::from_str(&recv_payload(&negotiated_subscriber).await)?;
+    assert_eq!(value, serde_json::json!({"speed": 9.88}));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_leader_election_and_failover() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let election_config = || OrchestratorConfig {
+        leader_election: true,
+        ..Default::default()
+    };
+    let orchestrator_a = Orchestrator::with_config(
+        "election_a".to_string(),
+        create_zenoh_session().await,
+        election_config(),
+    )
+    .await?;
+    let orchestrator_b = Orchestrator::with_config(
+        "election_b".to_string(),
+        create_zenoh_session().await,
+        election_config(),
+    )
+    .await?;
+
+    let cancel_a = CancellationToken::new();
+    let cancel_b = CancellationToken::new();
+    let handle_a = {
+        let orchestrator = orchestrator_a.clone();
+        let cancel = cancel_a.clone();
+        tokio::spawn(async move { orchestrator.run(cancel).await })
+    };
+    let handle_b = {
+        let orchestrator = orchestrator_b.clone();
+        let cancel = cancel_b.clone();
+        tokio::spawn(async move { orchestrator.run(cancel).await })
+    };
+
+    sleep(Duration::from_secs(1)).await;
+
+    assert!(orchestrator_a.is_leader());
+    assert!(!orchestrator_b.is_leader());
+
+    // Followers stay passive
+    let config = NodeConfig {
+        node_id: "election_node".to_string(),
+        config: serde_json::json!({}),
+    };
+    assert!(matches!(
+        orchestrator_b
+            .publish_node_config("election_node", &config)
+            .await,
+        Err(FabricError::NotLeader(_))
+    ));
+
+    cancel_a.cancel();
+    let _ = tokio::time::timeout(Duration::from_secs(5), handle_a).await;
+
+    let mut took_over = false;
+    for _ in 0..30 {
+        sleep(Duration::from_millis(100)).await;
+        if orchestrator_b.is_leader() {
+            took_over = true;
+            break;
+        }
+    }
+    assert!(took_over, "follower did not take over leadership");
+    orchestrator_b
+        .publish_node_config("election_node", &config)
+        .await?;
+
+    cancel_b.cancel();
+    let _ = tokio::time::timeout(Duration::from_secs(5), handle_b).await;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_subscribers_receive_negotiated_encoding() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let new_node = |id: &str| {
+        Node::new(
+            id.to_string(),
+            "generic".to_string(),
+            NodeConfig {
+                node_id: id.to_string(),
+                config: serde_json::json!({}),
+            },
+            session.clone(),
+            None,
+        )
+    };
+    let producer = new_node("negotiation_producer").await?;
+    let consumer = new_node("negotiation_consumer").await?;
+
+    let (tx, mut rx) = mpsc::channel(10);
+    for encoding in [PayloadEncoding::Json, PayloadEncoding::Cbor] {
+        let tx = tx.clone();
+        consumer
+            .create_subscriber_with_encoding(
+                "test_negotiation_topic".to_string(),
+                encoding,
+                Arc::new(Mutex::new(move |sample: Sample| {
+                    let _ = tx.try_send((
+                        encoding,
+                        sample.value.encoding.to_string(),
+                        encoding.decode::<serde_json::Value>(&sample.value.payload),
+                    ));
+                })),
+            )
+            .await?;
+    }
+
+    // Let the producer's matching status see both subscribers
+    sleep(Duration::from_millis(200)).await;
+
+    let reading = serde_json::json!({"temperature": 21.5});
+    producer
+        .publish_negotiated("test_negotiation_topic", &reading)
+        .await?;
+
+    let mut received = HashMap::new();
+    for _ in 0..2 {
+        let (encoding, wire_encoding, decoded) =
+            tokio::time::timeout(Duration::from_secs(2), rx.recv())
+                .await
+                .expect("negotiated sample not delivered")
+                .unwrap();
+        assert_eq!(decoded?, reading);
+        received.insert(encoding, wire_encoding);
+    }
+    assert_eq!(received[&PayloadEncoding::Json], "application/json");
+    assert_eq!(received[&PayloadEncoding::Cbor], "application/cbor");
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_heartbeats_continue_during_config_flood() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let heartbeats = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let heartbeats_clone = heartbeats.clone();
+    let _status_subscriber = session
+        .declare_subscriber("fabric/flooded_node/status")
+        .callback(move |_sample| {
+            heartbeats_clone.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        })
+        .res()
+        .await?;
+
+    let node = Node::new(
+        "flooded_node".to_string(),
+        "generic".to_string(),
+        NodeConfig {
+            node_id: "flooded_node".to_string(),
+            config: serde_json::json!({}),
+        },
+        session.clone(),
+        None,
+    )
+    .await?;
+    let cancel = CancellationToken::new();
+    let cancel_clone = cancel.clone();
+    let node_handle = tokio::spawn(async move { node.run(cancel_clone).await });
+
+    sleep(Duration::from_millis(200)).await;
+    let before = heartbeats.load(std::sync::atomic::Ordering::SeqCst);
+
+    // Flood from a plain thread so the single runtime worker only serves the node
+    let floods: Vec<_> = (0..4)
+        .map(|_| {
+            let flood_session = session.clone();
+            std::thread::spawn(move || {
+                use zenoh::prelude::sync::SyncResolve;
+                let deadline = std::time::Instant::now() + Duration::from_secs(3);
+                let mut sequence = 0u64;
+                while std::time::Instant::now() < deadline {
+                    sequence += 1;
+                    flood_session
+                        .put(
+                            "fabric/flooded_node/config/merge",
+                            serde_json::json!({"sequence": sequence}).to_string(),
+                        )
+                        .res_sync()
+                        .unwrap();
+                }
+            })
+        })
+        .collect();
+    tokio::task::spawn_blocking(move || {
+        for flood in floods {
+            flood.join().unwrap();
+        }
+    })
+    .await
+    .unwrap();
+
+    let during = heartbeats.load(std::sync::atomic::Ordering::SeqCst) - before;
+    assert!(during >= 2, "only {} heartbeats during the flood", during);
+
+    cancel.cancel();
+    let _ = tokio::time::timeout(Duration::from_secs(5), node_handle).await;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_graceful_shutdown_closes_components() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator =
+        Orchestrator::new("test_shutdown_orchestrator".to_string(), session.clone()).await?;
+    let node = Node::new(
+        "shutdown_node".to_string(),
+        "generic".to_string(),
+        NodeConfig {
+            node_id: "shutdown_node".to_string(),
+            config: serde_json::json!({}),
+        },
+        session.clone(),
+        None,
+    )
+    .await?;
+
+    let mut components = Components::new();
+    components.register(orchestrator.clone());
+    components.register(Arc::new(node.clone()));
+
+    let orchestrator_cancel = components.token();
+    let orchestrator_handle =
+        tokio::spawn(async move { orchestrator.run(orchestrator_cancel).await });
+    let node_cancel = components.token();
+    let node_clone = node.clone();
+    let node_handle = tokio::spawn(async move { node_clone.run(node_cancel).await });
+
+    wait_for_node_initialization().await;
+
+    let status_subscriber = session
+        .declare_subscriber("fabric/shutdown_node/status")
+        .res()
+        .await?;
+
+    let start = std::time::Instant::now();
+    let failed = shutdown::graceful(&components, Duration::from_secs(2)).await;
+    assert!(failed.is_empty(), "components failed to stop: {:?}", failed);
+    assert!(start.elapsed() < Duration::from_secs(2));
+    assert!(components.token().is_cancelled());
+
+    // The node announced it is going away before undeclaring
+    let mut offline_reported = false;
+    while let Ok(Ok(sample)) =
+        tokio::time::timeout(Duration::from_secs(1), status_subscriber.recv_async()).await
+    {
+        let node_data: NodeData = serde_json::from_slice(&sample.value.payload.contiguous())?;
+        if node_data.status == NodeStatus::Offline {
+            offline_reported = true;
+            break;
+        }
+    }
+    assert!(offline_reported);
+
+    let _ = tokio::time::timeout(Duration::from_secs(5), node_handle).await;
+    let _ = tokio::time::timeout(Duration::from_secs(5), orchestrator_handle).await;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_config_changes_reach_every_subscriber() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let node = Node::new(
+        "config_changes_node".to_string(),
+        "generic".to_string(),
+        NodeConfig {
+            node_id: "config_changes_node".to_string(),
+            config: serde_json::json!({"rate": 1}),
+        },
+        session.clone(),
+        None,
+    )
+    .await?;
+
+    let mut first = Box::pin(node.subscribe_config_changes());
+    let mut second = Box::pin(node.subscribe_config_changes());
+
+    let replaced = NodeConfig {
+        node_id: "config_changes_node".to_string(),
+        config: serde_json::json!({"rate": 5}),
+    };
+    node.set_config(replaced.clone()).await?;
+    node.update_config(NodeConfig {
+        node_id: "config_changes_node".to_string(),
+        config: serde_json::json!({"mode": "fast"}),
+    })
+    .await?;
+    let merged = NodeConfig {
+        node_id: "config_changes_node".to_string(),
+        config: serde_json::json!({"rate": 5, "mode": "fast"}),
+    };
+
+    for changes in [&mut first, &mut second] {
+        for expected in [&replaced, &merged] {
+            let received = tokio::time::timeout(Duration::from_secs(1), changes.next())
+                .await
+                .expect("config change not delivered");
+            assert_eq!(received.as_ref(), Some(expected));
+        }
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_clock_skew_detected() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator =
+        Orchestrator::new("test_skew_orchestrator".to_string(), session.clone()).await?;
+    let cancel = CancellationToken::new();
+    let orchestrator_clone = orchestrator.clone();
+    let orchestrator_cancel = cancel.clone();
+    let orchestrator_handle =
+        tokio::spawn(async move { orchestrator_clone.run(orchestrator_cancel).await });
+    sleep(Duration::from_millis(200)).await;
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    for (node_id, timestamp) in [("skew_future_node", now + 3600), ("skew_synced_node", now)] {
+        let node_data = NodeData::from_fields(
+            node_id.to_string(),
+            "generic".to_string(),
+            timestamp,
+            None,
+            NodeStatus::Online,
+        );
+        session
+            .put(format!("fabric/{}/status", node_id), node_data.to_json()?)
+            .res()
+            .await?;
+    }
+    sleep(Duration::from_millis(300)).await;
+
+    let skew = orchestrator
+        .get_clock_skew("skew_future_node")
+        .await
+        .expect("skew not measured");
+    assert!(skew >= Duration::from_secs(3590));
+    assert!(
+        orchestrator
+            .get_clock_skew("skew_synced_node")
+            .await
+            .expect("skew not measured")
+            <= Duration::from_secs(2)
+    );
+    assert!(orchestrator
+        .get_clock_skew("skew_unknown_node")
+        .await
+        .is_none());
+
+    let nodes = orchestrator.get_nodes().await;
+    assert!(nodes["skew_future_node"].clock_skewed);
+    assert!(!nodes["skew_synced_node"].clock_skewed);
+
+    cancel.cancel();
+    let _ = tokio::time::timeout(Duration::from_secs(5), orchestrator_handle).await;
+
+    Ok(())
+}
+
+#[test]
+fn test_node_status_wire_format() -> fabric::Result<()> {
+    let mut node_data = NodeData::new("status_node".to_string());
+    assert_eq!(node_data.status(), NodeStatus::Online);
+    node_data.set_status(NodeStatus::Degraded)?;
+    assert_eq!(
+        node_data.to_value(Default::default())?["status"],
+        "degraded"
+    );
+
+    // Statuses stay plain lowercase strings on the wire
+    let parsed = NodeData::from_json(
+        r#"{"node_id": "status_node", "node_type": "generic", "timestamp": 0, "metadata": null, "status": "offline"}"#,
+    )?;
+    assert_eq!(parsed.status(), NodeStatus::Offline);
+
+    // Statuses from newer or foreign nodes don't break parsing
+    let parsed = NodeData::from_json(
+        r#"{"node_id": "status_node", "node_type": "generic", "timestamp": 0, "metadata": null, "status": "rebooting"}"#,
+    )?;
+    assert_eq!(parsed.status(), NodeStatus::Unknown);
+
+    Ok(())
+}
+
+// Minimal HTTP/1.1 client, enough to exercise the REST API without another dependency
+#[cfg(feature = "rest")]
+async fn http_request(
+    addr: std::net::SocketAddr,
+    method: &str,
+    path: &str,
+    body: Option<&str>,
+) -> (u16, String) {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+    let body = body.unwrap_or_default();
+    let request = format!(
+        "{} {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+        method,
+        path,
+        addr,
+        body.len(),
+        body
+    );
+    stream.write_all(request.as_bytes()).await.unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    let status = head.split_whitespace().nth(1).unwrap().parse().unwrap();
+    (status, body.to_string())
+}
+
+#[cfg(feature = "rest")]
+async fn start_rest_orchestrator(
+    id: &str,
+    session: Arc<Session>,
+) -> fabric::Result<(Arc<Orchestrator>, std::net::SocketAddr, CancellationToken)> {
+    let orchestrator = Orchestrator::new(id.to_string(), session).await?;
+    let cancel = CancellationToken::new();
+    let orchestrator_clone = orchestrator.clone();
+    let orchestrator_cancel = cancel.clone();
+    tokio::spawn(async move { orchestrator_clone.run(orchestrator_cancel).await });
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    tokio::spawn(fabric::rest::serve_rest_listener(
+        listener,
+        orchestrator.clone(),
+    ));
+    Ok((orchestrator, addr, cancel))
+}
+
+#[cfg(feature = "rest")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_rest_lists_nodes() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let (orchestrator, addr, cancel) =
+        start_rest_orchestrator("test_rest_list_orchestrator", session).await?;
+    for node_id in ["rest_node_b", "rest_node_a"] {
+        orchestrator
+            .update_node_state(NodeData::from_fields(
+                node_id.to_string(),
+                "generic".to_string(),
+                0,
+                None,
+                NodeStatus::Online,
+            ))
+            .await;
+    }
+
+    let (status, body) = http_request(addr, "GET", "/nodes", None).await;
+    assert_eq!(status, 200);
+    let nodes: Vec<NodeData> = serde_json::from_str(&body)?;
+    let node_ids: Vec<&str> = nodes.iter().map(|node| node.node_id.as_str()).collect();
+    assert_eq!(node_ids, vec!["rest_node_a", "rest_node_b"]);
+
+    let (status, body) = http_request(addr, "GET", "/nodes/rest_node_a", None).await;
+    assert_eq!(status, 200);
+    assert_eq!(
+        serde_json::from_str::<NodeData>(&body)?.node_id,
+        "rest_node_a"
+    );
+
+    let (status, _) = http_request(addr, "GET", "/nodes/rest_node_missing", None).await;
+    assert_eq!(status, 404);
+
+    cancel.cancel();
+    Ok(())
+}
+
+#[cfg(feature = "rest")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_rest_publishes_node_config() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let (orchestrator, addr, cancel) =
+        start_rest_orchestrator("test_rest_config_orchestrator", session.clone()).await?;
+    let subscriber = session
+        .declare_subscriber("fabric/rest_config_node/config")
+        .res()
+        .await?;
+
+    let config = NodeConfig {
+        node_id: "rest_config_node".to_string(),
+        config: serde_json::json!({"rate": 20}),
+    };
+    let (status, _) = http_request(
+        addr,
+        "POST",
+        "/nodes/rest_config_node/config",
+        Some(&serde_json::to_string(&config)?),
+    )
+    .await;
+    assert_eq!(status, 204);
+
+    let sample = tokio::time::timeout(Duration::from_secs(2), subscriber.recv_async())
+        .await
+        .expect("config not published")
+        .unwrap();
+    let published: NodeConfig = serde_json::from_slice(&sample.value.payload.contiguous())?;
+    assert_eq!(published, config);
+    assert_eq!(
+        orchestrator.get_published_config("rest_config_node").await,
+        Some(config.clone())
+    );
+
+    // A config addressed to another node is rejected
+    let (status, body) = http_request(
+        addr,
+        "POST",
+        "/nodes/rest_other_node/config",
+        Some(&serde_json::to_string(&config)?),
+    )
+    .await;
+    assert_eq!(status, 400);
+    assert!(body.contains("rest_config_node"));
+
+    cancel.cancel();
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_configurable_offline_timeout() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator = Orchestrator::with_timeouts(
+        "test_timeouts_orchestrator".to_string(),
+        session.clone(),
+        Duration::from_secs(2),
+        Duration::from_millis(100),
+    )
+    .await?;
+    let cancel = CancellationToken::new();
+    let orchestrator_clone = orchestrator.clone();
+    let orchestrator_cancel = cancel.clone();
+    let orchestrator_handle =
+        tokio::spawn(async move { orchestrator_clone.run(orchestrator_cancel).await });
+
+    orchestrator
+        .update_node_state(NodeData::from_fields(
+            "timeouts_node".to_string(),
+            "generic".to_string(),
+            0,
+            None,
+            NodeStatus::Online,
+        ))
+        .await;
+
+    sleep(Duration::from_millis(1500)).await;
+    assert_eq!(
+        orchestrator.get_nodes().await["timeouts_node"]
+            .last_value
+            .status,
+        NodeStatus::Online
+    );
+
+    sleep(Duration::from_millis(1000)).await;
+    let nodes = orchestrator.get_nodes().await;
+    assert_eq!(
+        nodes["timeouts_node"].last_value.status,
+        NodeStatus::Offline
+    );
+    assert_eq!(
+        nodes["timeouts_node"].offline_reason.as_deref(),
+        Some(OFFLINE_REASON_HEARTBEAT_TIMEOUT)
+    );
+
+    cancel.cancel();
+    let _ = tokio::time::timeout(Duration::from_secs(5), orchestrator_handle).await;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_subscriber_preserves_sample_order() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let node = Node::with_options(
+        "ordering_node".to_string(),
+        "generic".to_string(),
+        NodeConfig {
+            node_id: "ordering_node".to_string(),
+            config: serde_json::json!({}),
+        },
+        session.clone(),
+        None,
+        // Room for the whole burst, so ordering is tested without drops
+        NodeOptions {
+            ingest_capacity: Some(500),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let received_clone = received.clone();
+    node.create_subscriber(
+        "test_ordering_topic".to_string(),
+        Arc::new(Mutex::new(move |sample: Sample| {
+            let sequence: u32 = String::from_utf8_lossy(&sample.value.payload.contiguous())
+                .parse()
+                .unwrap();
+            received_clone.lock().unwrap().push(sequence);
+        })),
+    )
+    .await?;
+
+    let publisher = session
+        .declare_publisher("test_ordering_topic")
+        .res()
+        .await?;
+    for sequence in 0..500u32 {
+        publisher.put(sequence.to_string()).res().await?;
+    }
+
+    for _ in 0..50 {
+        if received.lock().unwrap().len() == 500 {
+            break;
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    assert_eq!(*received.lock().unwrap(), (0..500).collect::<Vec<u32>>());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_cbor_node_and_orchestrator_round_trip() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator = Orchestrator::with_config(
+        "test_cbor_orchestrator".to_string(),
+        session.clone(),
+        OrchestratorConfig {
+            encoding: PayloadEncoding::Cbor,
+            ..Default::default()
+        },
+    )
+    .await?;
+    let node = Node::with_options(
+        "cbor_node".to_string(),
+        "generic".to_string(),
+        NodeConfig {
+            node_id: "cbor_node".to_string(),
+            config: serde_json::json!({}),
+        },
+        session.clone(),
+        None,
+        NodeOptions {
+            encoding: PayloadEncoding::Cbor,
+            ..Default::default()
+        },
+    )
+    .await?;
+    let status_subscriber = session
+        .declare_subscriber("fabric/cbor_node/status")
+        .res()
+        .await?;
+
+    let cancel = CancellationToken::new();
+    let orchestrator_clone = orchestrator.clone();
+    let orchestrator_cancel = cancel.clone();
+    let orchestrator_handle =
+        tokio::spawn(async move { orchestrator_clone.run(orchestrator_cancel).await });
+    let node_clone = node.clone();
+    let node_cancel = cancel.clone();
+    let node_handle = tokio::spawn(async move { node_clone.run(node_cancel).await });
+
+    // Statuses go out as CBOR and the orchestrator still understands them
+    let sample = tokio::time::timeout(Duration::from_secs(5), status_subscriber.recv_async())
+        .await
+        .expect("no status published")
+        .unwrap();
+    assert_eq!(sample.value.encoding.to_string(), "application/cbor");
+    let status: NodeData = PayloadEncoding::Cbor.decode(&sample.value.payload)?;
+    assert_eq!(status.node_id, "cbor_node");
+
+    wait_for_node_initialization().await;
+    assert_eq!(
+        orchestrator.get_nodes().await["cbor_node"]
+            .last_value
+            .status,
+        NodeStatus::Online
+    );
+
+    // Configs go the other way as CBOR too
+    let config = NodeConfig {
+        node_id: "cbor_node".to_string(),
+        config: serde_json::json!({"rate": 50, "mode": "burst"}),
+    };
+    orchestrator
+        .publish_node_config("cbor_node", &config)
+        .await?;
+    let mut applied = false;
+    for _ in 0..20 {
+        sleep(Duration::from_millis(100)).await;
+        if node.get_config().await == config {
+            applied = true;
+            break;
+        }
+    }
+    assert!(applied, "CBOR config never reached the node");
+
+    cancel.cancel();
+    let _ = tokio::time::timeout(Duration::from_secs(5), node_handle).await;
+    let _ = tokio::time::timeout(Duration::from_secs(5), orchestrator_handle).await;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_orchestrator_merges_incremental_metadata() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator = Orchestrator::with_config(
+        "test_merge_metadata_orchestrator".to_string(),
+        session.clone(),
+        OrchestratorConfig {
+            merge_metadata: true,
+            ..Default::default()
+        },
+    )
+    .await?;
+    let cancel = CancellationToken::new();
+    let orchestrator_clone = orchestrator.clone();
+    let orchestrator_cancel = cancel.clone();
+    let orchestrator_handle =
+        tokio::spawn(async move { orchestrator_clone.run(orchestrator_cancel).await });
+    sleep(Duration::from_millis(200)).await;
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    for metadata in [serde_json::json!({"a": 1}), serde_json::json!({"b": 2})] {
+        let node_data = NodeData::from_fields(
+            "merge_metadata_node".to_string(),
+            "generic".to_string(),
+            now,
+            Some(metadata),
+            NodeStatus::Online,
+        );
+        session
+            .put("fabric/merge_metadata_node/status", node_data.to_json()?)
+            .res()
+            .await?;
+        sleep(Duration::from_millis(200)).await;
+    }
+
+    let nodes = orchestrator.get_nodes().await;
+    assert_eq!(
+        nodes["merge_metadata_node"].last_value.metadata,
+        Some(serde_json::json!({"a": 1, "b": 2}))
+    );
+
+    cancel.cancel();
+    let _ = tokio::time::timeout(Duration::from_secs(5), orchestrator_handle).await;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_spawned_node_shuts_down_through_handle() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let node = Node::new(
+        "handle_node".to_string(),
+        "generic".to_string(),
+        NodeConfig {
+            node_id: "handle_node".to_string(),
+            config: serde_json::json!({}),
+        },
+        session.clone(),
+        None,
+    )
+    .await?;
+    let status_subscriber = session
+        .declare_subscriber("fabric/handle_node/status")
+        .res()
+        .await?;
+
+    let handle = node.spawn(CancellationToken::new());
+    assert_eq!(handle.node_id(), "handle_node");
+    tokio::time::timeout(Duration::from_secs(5), status_subscriber.recv_async())
+        .await
+        .expect("spawned node never reported status")
+        .unwrap();
+    assert!(!handle.is_finished());
+
+    handle.shutdown().await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_orchestrator_rejects_disallowed_node_types() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator = Orchestrator::with_config(
+        "test_allowed_types_orchestrator".to_string(),
+        session.clone(),
+        OrchestratorConfig {
+            allowed_node_types: ["quadcopter".to_string()].into_iter().collect(),
+            ..Default::default()
+        },
+    )
+    .await?;
+    let cancel = CancellationToken::new();
+    let orchestrator_clone = orchestrator.clone();
+    let orchestrator_cancel = cancel.clone();
+    let orchestrator_handle =
+        tokio::spawn(async move { orchestrator_clone.run(orchestrator_cancel).await });
+    sleep(Duration::from_millis(200)).await;
+
+    for (node_id, node_type) in [
+        ("allowed_quadcopter_node", "quadcopter"),
+        ("disallowed_rover_node", "rover"),
+    ] {
+        let node_data = NodeData::from_fields(
+            node_id.to_string(),
+            node_type.to_string(),
+            0,
+            None,
+            NodeStatus::Online,
+        );
+        session
+            .put(format!("fabric/{}/status", node_id), node_data.to_json()?)
+            .res()
+            .await?;
+    }
+    sleep(Duration::from_millis(300)).await;
+
+    let nodes = orchestrator.get_nodes().await;
+    assert!(nodes.contains_key("allowed_quadcopter_node"));
+    assert!(!nodes.contains_key("disallowed_rover_node"));
+    assert!(nodes
+        .values()
+        .all(|state| state.last_value.node_type == "quadcopter"));
+    // Status traffic from other tests on the same network is dropped and counted too
+    assert!(orchestrator.metrics().await.disallowed_type_dropped >= 1);
+
+    cancel.cancel();
+    let _ = tokio::time::timeout(Duration::from_secs(5), orchestrator_handle).await;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_orchestrator_queries_state_of_earlier_node() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let node = Node::new(
+        "early_state_node".to_string(),
+        "generic".to_string(),
+        NodeConfig {
+            node_id: "early_state_node".to_string(),
+            config: serde_json::json!({}),
+        },
+        session.clone(),
+        None,
+    )
+    .await?;
+    let cancel = CancellationToken::new();
+    let node_handle = node.spawn(cancel.clone());
+    wait_for_node_initialization().await;
+
+    // Started after the node's status went out, so it has missed it
+    let orchestrator =
+        Orchestrator::new("test_early_state_orchestrator".to_string(), session.clone()).await?;
+    assert!(orchestrator.get_nodes().await.is_empty());
+
+    let state = orchestrator
+        .query_node_state("early_state_node")
+        .await?
+        .expect("node should answer the state query");
+    assert_eq!(state.node_id, "early_state_node");
+    assert_eq!(state.status, NodeStatus::Online);
+    assert!(orchestrator
+        .get_nodes()
+        .await
+        .contains_key("early_state_node"));
+
+    // Nothing answers for a node that doesn't exist
+    assert!(orchestrator
+        .query_node_state("no_such_node")
+        .await?
+        .is_none());
+
+    node_handle.shutdown().await?;
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_json_patch_config() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator =
+        Orchestrator::new("test_json_patch_orchestrator".to_string(), session.clone()).await?;
+    let node = Node::new(
+        "json_patch_node".to_string(),
+        "generic".to_string(),
+        NodeConfig {
+            node_id: "json_patch_node".to_string(),
+            config: serde_json::json!({"rate": 10, "mode": "idle"}),
+        },
+        session.clone(),
+        None,
+    )
+    .await?;
+    let handle = node.clone().spawn(CancellationToken::new());
+    wait_for_node_initialization().await;
+
+    let patch = serde_json::from_value(serde_json::json!([
+        {"op": "test", "path": "/mode", "value": "idle"},
+        {"op": "replace", "path": "/rate", "value": 20},
+        {"op": "add", "path": "/gain", "value": 1.5},
+    ]))?;
+    orchestrator
+        .apply_json_patch("json_patch_node", patch)
+        .await?;
+    let expected = serde_json::json!({"rate": 20, "mode": "idle", "gain": 1.5});
+    let mut applied = false;
+    for _ in 0..20 {
+        sleep(Duration::from_millis(100)).await;
+        if node.get_config().await.config == expected {
+            applied = true;
+            break;
+        }
+    }
+    assert!(applied, "patch never reached the node");
+
+    handle.shutdown().await
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_json_patch_failed_test_op_leaves_config_unchanged() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator = Orchestrator::new(
+        "test_json_patch_reject_orchestrator".to_string(),
+        session.clone(),
+    )
+    .await?;
+    let original = serde_json::json!({"rate": 10, "mode": "idle"});
+    let node = Node::new(
+        "json_patch_reject_node".to_string(),
+        "generic".to_string(),
+        NodeConfig {
+            node_id: "json_patch_reject_node".to_string(),
+            config: original.clone(),
+        },
+        session.clone(),
+        None,
+    )
+    .await?;
+    let handle = node.clone().spawn(CancellationToken::new());
+    wait_for_node_initialization().await;
+
+    // The replace comes first, but the failing test still rejects the whole patch
+    let patch = serde_json::from_value(serde_json::json!([
+        {"op": "replace", "path": "/rate", "value": 20},
+        {"op": "test", "path": "/mode", "value": "active"},
+    ]))?;
+    orchestrator
+        .apply_json_patch("json_patch_reject_node", patch)
+        .await?;
+    sleep(Duration::from_millis(500)).await;
+    assert_eq!(node.get_config().await.config, original);
+
+    handle.shutdown().await
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_publish_node_configs_in_batch() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator =
+        Orchestrator::new("test_batch_orchestrator".to_string(), session.clone()).await?;
+    let config_subscriber = session
+        .declare_subscriber("fabric/batch_node_$*/config")
+        .res()
+        .await?;
+
+    let node_ids: Vec<String> = (0..5).map(|i| format!("batch_node_{}", i)).collect();
+    let configs: Vec<NodeConfig> = node_ids
+        .iter()
+        .map(|node_id| NodeConfig {
+            node_id: node_id.clone(),
+            config: serde_json::json!({"rate": 5}),
+        })
+        .collect();
+    let results = orchestrator.publish_node_configs(&configs).await?;
+
+    assert_eq!(results.len(), node_ids.len());
+    for ((node_id, result), expected) in results.iter().zip(&node_ids) {
+        assert_eq!(node_id, expected);
+        assert!(result.is_ok());
+    }
+
+    let mut received = std::collections::HashSet::new();
+    while received.len() < node_ids.len() {
+        let sample = tokio::time::timeout(Duration::from_secs(5), config_subscriber.recv_async())
+            .await
+            .expect("not every config was published")
+            .unwrap();
+        received.insert(sample.key_expr.as_str().to_string());
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_slow_subscriber_does_not_block_others() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let node = Node::new(
+        "slow_dispatch_node".to_string(),
+        "generic".to_string(),
+        NodeConfig {
+            node_id: "slow_dispatch_node".to_string(),
+            config: serde_json::json!({}),
+        },
+        session.clone(),
+        None,
+    )
+    .await?;
+
+    node.create_subscriber(
+        "test_slow_dispatch/slow".to_string(),
+        Arc::new(Mutex::new(|_sample: Sample| {
+            std::thread::sleep(Duration::from_secs(1));
+        })),
+    )
+    .await?;
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    node.create_subscriber(
+        "test_slow_dispatch/fast".to_string(),
+        Arc::new(Mutex::new(move |sample: Sample| {
+            tx.send(sample.value.payload.contiguous().to_vec()).unwrap();
+        })),
+    )
+    .await?;
+
+    for i in 0..3 {
+        session
+            .put("test_slow_dispatch/slow", format!("slow {}", i))
+            .res()
+            .await?;
+    }
+    let started = std::time::Instant::now();
+    session.put("test_slow_dispatch/fast", "fast").res().await?;
+
+    let payload = tokio::time::timeout(Duration::from_millis(500), rx.recv())
+        .await
+        .expect("fast subscriber was held up by the slow one")
+        .unwrap();
+    assert_eq!(payload, b"fast".to_vec());
+    assert!(started.elapsed() < Duration::from_millis(500));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_slow_subscriber_drops_instead_of_queueing() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let node = Node::with_options(
+        "slow_subscriber_node".to_string(),
+        "generic".to_string(),
+        NodeConfig {
+            node_id: "slow_subscriber_node".to_string(),
+            config: serde_json::json!({}),
+        },
+        session.clone(),
+        None,
+        NodeOptions {
+            ingest_capacity: Some(4),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let received_clone = received.clone();
+    node.create_subscriber(
+        "test_slow_subscriber_topic".to_string(),
+        Arc::new(Mutex::new(move |sample: Sample| {
+            std::thread::sleep(std::time::Duration::from_millis(20));
+            let sequence: u32 = String::from_utf8_lossy(&sample.value.payload.contiguous())
+                .parse()
+                .unwrap();
+            received_clone.lock().unwrap().push(sequence);
+        })),
+    )
+    .await?;
+
+    let publisher = session
+        .declare_publisher("test_slow_subscriber_topic")
+        .res()
+        .await?;
+    for sequence in 0..50u32 {
+        publisher.put(sequence.to_string()).res().await?;
+    }
+    sleep(Duration::from_millis(1000)).await;
+
+    // Samples beyond the queue are dropped and counted; the rest keep their order
+    let received = received.lock().unwrap().clone();
+    assert!(node.ingest_dropped() > 0);
+    assert_eq!(received.len() as u64 + node.ingest_dropped(), 50);
+    assert!(received.windows(2).all(|pair| pair[0] < pair[1]));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_node_unsubscribe_from_topic() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let node = Node::new(
+        "unsubscribe_node".to_string(),
+        "generic".to_string(),
+        NodeConfig {
+            node_id: "unsubscribe_node".to_string(),
+            config: serde_json::json!({}),
+        },
+        session.clone(),
+        None,
+    )
+    .await?;
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    node.create_subscriber(
+        "test_unsubscribe_topic".to_string(),
+        Arc::new(Mutex::new(move |sample: Sample| {
+            tx.send(sample.value.payload.contiguous().to_vec()).unwrap();
+        })),
+    )
+    .await?;
+
+    session
+        .put("test_unsubscribe_topic", "before")
+        .res()
+        .await?;
+    let payload = tokio::time::timeout(Duration::from_secs(2), rx.recv())
+        .await
+        .expect("subscriber never received a sample")
+        .unwrap();
+    assert_eq!(payload, b"before".to_vec());
+
+    node.unsubscribe_from_topic("test_unsubscribe_topic")
+        .await?;
+    session.put("test_unsubscribe_topic", "after").res().await?;
+    sleep(Duration::from_millis(300)).await;
+    assert!(rx.try_recv().is_err());
+
+    assert!(matches!(
+        node.unsubscribe_from_topic("test_unsubscribe_topic").await,
+        Err(FabricError::Other(_))
+    ));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_open_session_retries_unreachable_endpoint() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let options = SessionOptions {
+        connect: vec!["tcp/127.0.0.1:9".to_string()],
+        mode: config::WhatAmI::Client,
+        max_attempts: 3,
+        retry: RetryConfig {
+            initial_interval: Duration::from_millis(10),
+            randomization_factor: 0.0,
+            ..Default::default()
+        },
+    };
+    match open_session(&options).await {
+        Ok(_) => panic!("opened a session against an unreachable endpoint"),
+        Err(e) => assert!(
+            e.to_string().contains("after 3 attempts"),
+            "unexpected error: {}",
+            e
+        ),
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_nodes_page() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator =
+        Orchestrator::new("test_paging_orchestrator".to_string(), session.clone()).await?;
+    let cancel = CancellationToken::new();
+    let orchestrator_clone = orchestrator.clone();
+    let orchestrator_cancel = cancel.clone();
+    let orchestrator_handle =
+        tokio::spawn(async move { orchestrator_clone.run(orchestrator_cancel).await });
+    sleep(Duration::from_millis(200)).await;
+
+    let node_ids: Vec<String> = (0..50).map(|i| format!("page_node_{:02}", i)).collect();
+    for node_id in &node_ids {
+        let node_data = NodeData::from_fields(
+            node_id.clone(),
+            "generic".to_string(),
+            0,
+            None,
+            NodeStatus::Online,
+        );
+        session
+            .put(format!("fabric/{}/status", node_id), node_data.to_json()?)
+            .res()
+            .await?;
+    }
+    for _ in 0..20 {
+        if orchestrator.get_nodes().await.len() == node_ids.len() {
+            break;
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+
+    let mut paged = Vec::new();
+    for page_index in 0..5 {
+        let (page, total) = orchestrator.get_nodes_page(page_index * 10, 10).await;
+        assert_eq!(total, 50);
+        assert_eq!(page.len(), 10);
+        paged.extend(page.into_iter().map(|(node_id, _)| node_id));
+    }
+    assert_eq!(paged, node_ids);
+
+    let (page, total) = orchestrator.get_nodes_page(50, 10).await;
+    assert!(page.is_empty());
+    assert_eq!(total, 50);
+
+    cancel.cancel();
+    let _ = tokio::time::timeout(Duration::from_secs(5), orchestrator_handle).await;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_lightweight_heartbeat_payload() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator = Orchestrator::new(
+        "test_lightweight_heartbeat_orchestrator".to_string(),
+        session.clone(),
+    )
+    .await?;
+    let cancel = CancellationToken::new();
+    let orchestrator_clone = orchestrator.clone();
+    let orchestrator_cancel = cancel.clone();
+    let orchestrator_handle =
+        tokio::spawn(async move { orchestrator_clone.run(orchestrator_cancel).await });
+    sleep(Duration::from_millis(200)).await;
+
+    let node = Node::with_options(
+        "lightweight_heartbeat_node".to_string(),
+        "generic".to_string(),
+        NodeConfig {
+            node_id: "lightweight_heartbeat_node".to_string(),
+            config: serde_json::json!({}),
+        },
+        session.clone(),
+        None,
+        NodeOptions {
+            lightweight_heartbeat: true,
+            ..Default::default()
+        },
+    )
+    .await?;
+    let status_subscriber = session
+        .declare_subscriber("fabric/lightweight_heartbeat_node/status")
+        .res()
+        .await?;
+    let handle = node.spawn(cancel.clone());
+
+    // The first report is full so the orchestrator learns the node type
+    let mut payloads = Vec::new();
+    for _ in 0..2 {
+        let sample = tokio::time::timeout(Duration::from_secs(5), status_subscriber.recv_async())
+            .await
+            .expect("no status published")
+            .unwrap();
+        let payload: serde_json::Value =
+            serde_json::from_slice(&sample.value.payload.contiguous())?;
+        payloads.push(payload);
+    }
+    assert_eq!(payloads[0]["node_type"], "generic");
+    let mut keys: Vec<&String> = payloads[1].as_object().unwrap().keys().collect();
+    keys.sort();
+    assert_eq!(keys, ["id", "status", "ts"]);
+    assert_eq!(payloads[1]["id"], "lightweight_heartbeat_node");
+    assert_eq!(payloads[1]["status"], "online");
+
+    // Heartbeats keep the node online without losing what the full report said
+    sleep(Duration::from_millis(300)).await;
+    let nodes = orchestrator.get_nodes().await;
+    let node_state = &nodes["lightweight_heartbeat_node"];
+    assert_eq!(node_state.last_value.status, NodeStatus::Online);
+    assert_eq!(node_state.last_value.node_type, "generic");
+
+    handle.shutdown().await?;
+    let _ = tokio::time::timeout(Duration::from_secs(5), orchestrator_handle).await;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_lightweight_data_uses_node_encoding() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let node = Node::with_options(
+        "lightweight_cbor_node".to_string(),
+        "generic".to_string(),
+        NodeConfig {
+            node_id: "lightweight_cbor_node".to_string(),
+            config: serde_json::json!({}),
+        },
+        session.clone(),
+        None,
+        NodeOptions {
+            lightweight_heartbeat: true,
+            encoding: PayloadEncoding::Cbor,
+            ..Default::default()
+        },
+    )
+    .await?;
+    let data_subscriber = session
+        .declare_subscriber("fabric/lightweight_cbor_node/data")
+        .res()
+        .await?;
+
+    // The full NodeData moved off the status topic goes out like a status would
+    node.tick().await?;
+    let sample = tokio::time::timeout(Duration::from_secs(5), data_subscriber.recv_async())
+        .await
+        .expect("no data published")
+        .unwrap();
+    assert_eq!(sample.value.encoding.to_string(), "application/cbor");
+    let node_data: NodeData = PayloadEncoding::Cbor.decode(&sample.value.payload)?;
+    assert_eq!(node_data.node_id, "lightweight_cbor_node");
+    assert_eq!(node_data.node_type, "generic");
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_orchestrator_metrics_counts() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator = Orchestrator::with_config(
+        "test_metrics_orchestrator".to_string(),
+        session.clone(),
+        OrchestratorConfig {
+            // Only count this test's nodes, not status traffic from tests running alongside
+            allowed_node_types: ["metrics_type".to_string()].into_iter().collect(),
+            offline_timeout: Duration::from_millis(500),
+            health_check_interval: Duration::from_millis(100),
+            ..Default::default()
+        },
+    )
+    .await?;
+    let cancel = CancellationToken::new();
+    let orchestrator_clone = orchestrator.clone();
+    let orchestrator_cancel = cancel.clone();
+    let orchestrator_handle =
+        tokio::spawn(async move { orchestrator_clone.run(orchestrator_cancel).await });
+    sleep(Duration::from_millis(200)).await;
+
+    for node_id in ["metrics_node_a", "metrics_node_a", "metrics_node_b"] {
+        let node_data = NodeData::now(node_id.to_string(), "metrics_type".to_string());
+        session
+            .put(format!("fabric/{}/status", node_id), node_data.to_json()?)
+            .res()
+            .await?;
+    }
+    orchestrator
+        .publish_node_config(
+            "metrics_node_a",
+            &NodeConfig {
+                node_id: "metrics_node_a".to_string(),
+                config: serde_json::json!({"rate": 1}),
+            },
+        )
+        .await?;
+    sleep(Duration::from_millis(200)).await;
+
+    let metrics = orchestrator.metrics().await;
+    assert_eq!(metrics.status_updates_total, 3);
+    assert_eq!(metrics.configs_published_total, 1);
+    assert_eq!(metrics.nodes_online, 2);
+    assert_eq!(metrics.nodes_offline, 0);
+
+    // Both nodes go quiet and time out
+    sleep(Duration::from_millis(1000)).await;
+    let metrics = orchestrator.metrics().await;
+    assert_eq!(metrics.offline_transitions_total, 2);
+    assert_eq!(metrics.nodes_online, 0);
+    assert_eq!(metrics.nodes_offline, 2);
+
+    cancel.cancel();
+    let _ = tokio::time::timeout(Duration::from_secs(5), orchestrator_handle).await;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_keepalives_do_not_fire_callbacks() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator =
+        Orchestrator::new("test_keepalive_orchestrator".to_string(), session.clone()).await?;
+    let calls = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let calls_clone = calls.clone();
+    orchestrator
+        .register_callback(
+            "keepalive_node",
+            Arc::new(Mutex::new(move |node_data: NodeData| {
+                calls_clone.lock().unwrap().push(node_data.status);
+            })),
+        )
+        .await?;
+    let cancel = CancellationToken::new();
+    let orchestrator_clone = orchestrator.clone();
+    let orchestrator_cancel = cancel.clone();
+    let orchestrator_handle =
+        tokio::spawn(async move { orchestrator_clone.run(orchestrator_cancel).await });
+    sleep(Duration::from_millis(200)).await;
+
+    let publish = |status: NodeStatus, timestamp: u64| {
+        let session = session.clone();
+        async move {
+            let node_data = NodeData::builder("keepalive_node".to_string())
+                .node_type("generic".to_string())
+                .status(status)
+                .timestamp(timestamp)
+                .build();
+            session
+                .put("fabric/keepalive_node/status", node_data.to_json()?)
+                .res()
+                .await?;
+            sleep(Duration::from_millis(100)).await;
+            fabric::Result::Ok(())
+        }
+    };
+
+    // Identical online reports and liveness pings only refresh liveness
+    for timestamp in 1..=4 {
+        publish(NodeStatus::Online, timestamp).await?;
+    }
+    session
+        .put(
+            "fabric/keepalive_node/status",
+            serde_json::json!({"id": "keepalive_node", "ts": 5, "status": "online"}).to_string(),
+        )
+        .res()
+        .await?;
+    sleep(Duration::from_millis(100)).await;
+    assert_eq!(*calls.lock().unwrap(), vec![NodeStatus::Online]);
+    assert_eq!(
+        orchestrator.get_nodes().await["keepalive_node"]
+            .last_value
+            .timestamp,
+        5
+    );
+
+    publish(NodeStatus::Degraded, 6).await?;
+    assert_eq!(
+        *calls.lock().unwrap(),
+        vec![NodeStatus::Online, NodeStatus::Degraded]
+    );
+
+    cancel.cancel();
+    let _ = tokio::time::timeout(Duration::from_secs(5), orchestrator_handle).await;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_transition_callback_receives_previous_value() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator =
+        Orchestrator::new("test_transition_orchestrator".to_string(), session).await?;
+    let transitions = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let transitions_clone = transitions.clone();
+    orchestrator
+        .register_transition_callback(
+            "transition_node",
+            Arc::new(Mutex::new(
+                move |previous: Option<NodeData>, node_data: NodeData| {
+                    transitions_clone
+                        .lock()
+                        .unwrap()
+                        .push((previous.map(|previous| previous.status), node_data.status));
+                },
+            )),
+        )
+        .await?;
+
+    for status in [NodeStatus::Online, NodeStatus::Offline] {
+        orchestrator
+            .update_node_state(
+                NodeData::builder("transition_node".to_string())
+                    .node_type("generic".to_string())
+                    .status(status)
+                    .build(),
+            )
+            .await;
+    }
+
+    assert_eq!(
+        *transitions.lock().unwrap(),
+        vec![
+            (None, NodeStatus::Online),
+            (Some(NodeStatus::Online), NodeStatus::Offline),
+        ]
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_pattern_callback_matches_node_ids() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator = Orchestrator::new("test_pattern_orchestrator".to_string(), session).await?;
+    let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let seen_clone = seen.clone();
+    orchestrator
+        .register_pattern_callback(
+            "sensor_*",
+            Arc::new(Mutex::new(move |node_data: NodeData| {
+                seen_clone.lock().unwrap().push(node_data.node_id);
+            })),
+        )
+        .await?;
+
+    for node_id in ["sensor_1", "radio_1"] {
+        orchestrator
+            .update_node_state(NodeData::now(node_id.to_string(), "generic".to_string()))
+            .await;
+    }
+
+    assert_eq!(*seen.lock().unwrap(), vec!["sensor_1".to_string()]);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_rollout_to_half_of_nodes() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator = Orchestrator::new("test_rollout_orchestrator".to_string(), session).await?;
+    let node_ids: Vec<String> = (1..=4).map(|i| format!("rollout_node_{}", i)).collect();
+    for node_id in &node_ids {
+        orchestrator
+            .update_node_state(NodeData::now(node_id.clone(), "rollout_type".to_string()))
+            .await;
+    }
+    orchestrator
+        .update_node_state(NodeData::now(
+            "rollout_other".to_string(),
+            "other_type".to_string(),
+        ))
+        .await;
+
+    let old_config = serde_json::json!({"version": 1});
+    let new_config = serde_json::json!({"version": 2});
+    let chosen = orchestrator
+        .rollout("rollout_type", &new_config, 50)
+        .await?;
+    assert_eq!(chosen.len(), 2);
+    assert_eq!(
+        orchestrator
+            .rollout("rollout_type", &new_config, 50)
+            .await?,
+        chosen
+    );
+    for node_id in &node_ids {
+        let published = orchestrator.get_published_config(node_id).await;
+        if chosen.contains(node_id) {
+            assert_eq!(published.unwrap().config, new_config);
+        } else {
+            assert!(published.is_none());
+        }
+    }
+    assert!(orchestrator
+        .get_published_config("rollout_other")
+        .await
+        .is_none());
+
+    orchestrator.rollback(&chosen, &old_config).await?;
+    for node_id in &chosen {
+        assert_eq!(
+            orchestrator
+                .get_published_config(node_id)
+                .await
+                .unwrap()
+                .config,
+            old_config
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_zid_matches_session() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let node = Node::new(
+        "zid_node".to_string(),
+        "generic".to_string(),
+        NodeConfig {
+            node_id: "zid_node".to_string(),
+            config: serde_json::json!({}),
+        },
+        session.clone(),
+        None,
+    )
+    .await?;
+
+    assert_eq!(node.zid(), session.zid());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_node_reports_offline_when_run_stops() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator = Orchestrator::new(
+        "test_stop_offline_orchestrator".to_string(),
+        session.clone(),
+    )
+    .await?;
+    let cancel = CancellationToken::new();
+    let orchestrator_clone = orchestrator.clone();
+    let orchestrator_cancel = cancel.clone();
+    let orchestrator_handle =
+        tokio::spawn(async move { orchestrator_clone.run(orchestrator_cancel).await });
+    sleep(Duration::from_millis(200)).await;
+
+    let node = Node::new(
+        "stop_offline_node".to_string(),
+        "generic".to_string(),
+        NodeConfig {
+            node_id: "stop_offline_node".to_string(),
+            config: serde_json::json!({}),
+        },
+        session.clone(),
+        None,
+    )
+    .await?;
+    let handle = node.spawn(CancellationToken::new());
+    wait_for_node_initialization().await;
+    assert_eq!(
+        orchestrator.get_nodes().await["stop_offline_node"]
+            .last_value
+            .status,
+        NodeStatus::Online
+    );
+
+    let stopped_at = std::time::Instant::now();
+    handle.shutdown().await?;
+    let mut status = NodeStatus::Online;
+    while stopped_at.elapsed() < Duration::from_secs(1) {
+        status = orchestrator.get_nodes().await["stop_offline_node"]
+            .last_value
+            .status;
+        if status == NodeStatus::Offline {
+            break;
+        }
+        sleep(Duration::from_millis(50)).await;
+    }
+    assert_eq!(status, NodeStatus::Offline);
+
+    cancel.cancel();
+    let _ = tokio::time::timeout(Duration::from_secs(5), orchestrator_handle).await;
+
+    Ok(())
+}
+
+// A peer listening on `endpoint` without scouting, standing in for a zenoh router
+async fn open_router(endpoint: &str) -> Session {
+    let mut config = config::peer();
+    config.listen.endpoints = vec![endpoint.parse().unwrap()];
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    zenoh::open(config).res().await.unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_node_resumes_config_after_router_restarts() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let endpoint = format!("tcp/127.0.0.1:{}", 20000 + std::process::id() % 20000);
+    let router = open_router(&endpoint).await;
+    let node_session = open_session(&SessionOptions {
+        connect: vec![endpoint.clone()],
+        mode: config::WhatAmI::Client,
+        ..Default::default()
+    })
+    .await?
+    .into_arc();
+    let node = Node::new(
+        "router_restart_node".to_string(),
+        "generic".to_string(),
+        NodeConfig {
+            node_id: "router_restart_node".to_string(),
+            config: serde_json::json!({}),
+        },
+        node_session,
+        None,
+    )
+    .await?;
+    let handle = node.clone().spawn(CancellationToken::new());
+    wait_for_node_initialization().await;
+
+    let put_config = |router: &Session, version: u64| {
+        let config = NodeConfig {
+            node_id: "router_restart_node".to_string(),
+            config: serde_json::json!({ "version": version }),
+        };
+        router
+            .put(
+                "fabric/router_restart_node/config",
+                serde_json::to_string(&config).unwrap(),
+            )
+            .res()
+    };
+
+    put_config(&router, 1).await?;
+    sleep(Duration::from_millis(300)).await;
+    assert_eq!(node.get_config().await.config["version"], 1);
+
+    // The node's only link goes away; its client session reconnects to the new router
+    // and the config subscription has to come back with it
+    router.close().res().await?;
+    sleep(Duration::from_millis(500)).await;
+    let router = open_router(&endpoint).await;
+    let reconnected = std::time::Instant::now();
+    let mut version = serde_json::Value::Null;
+    while reconnected.elapsed() < Duration::from_secs(10) {
+        put_config(&router, 2).await?;
+        sleep(Duration::from_millis(200)).await;
+        version = node.get_config().await.config["version"].clone();
+        if version == 2 {
+            break;
+        }
+    }
+    assert_eq!(version, 2);
+    assert!(!handle.is_finished());
+
+    handle.shutdown().await?;
+    router.close().res().await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_malformed_config_does_not_stop_node() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let node = Node::new(
+        "malformed_config_node".to_string(),
+        "generic".to_string(),
+        NodeConfig {
+            node_id: "malformed_config_node".to_string(),
+            config: serde_json::json!({ "rate": 1 }),
+        },
+        session.clone(),
+        None,
+    )
+    .await?;
+    let cancel = CancellationToken::new();
+    let node_handle = node.clone().spawn(cancel.clone());
+    wait_for_node_initialization().await;
+
+    session
+        .put("fabric/malformed_config_node/config", "not a config")
+        .res()
+        .await?;
+    session
+        .put("fabric/malformed_config_node/config/merge", "{ truncated")
+        .res()
+        .await?;
+    sleep(Duration::from_millis(300)).await;
+    assert_eq!(node.malformed_dropped(), 2);
+    assert_eq!(node.get_config().await.config["rate"], 1);
+
+    // The node keeps running and still applies the next valid config
+    let valid = NodeConfig {
+        node_id: "malformed_config_node".to_string(),
+        config: serde_json::json!({ "rate": 2 }),
+    };
+    session
+        .put(
+            "fabric/malformed_config_node/config",
+            serde_json::to_string(&valid)?,
+        )
+        .res()
+        .await?;
+    sleep(Duration::from_millis(300)).await;
+    assert_eq!(node.get_config().await.config["rate"], 2);
+
+    cancel.cancel();
+    node_handle.shutdown().await?;
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_pull_subscriber_fetches_on_demand() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let node = Node::new(
+        "pull_subscriber_node".to_string(),
+        "generic".to_string(),
+        NodeConfig {
+            node_id: "pull_subscriber_node".to_string(),
+            config: serde_json::json!({}),
+        },
+        create_zenoh_session().await,
+        None,
+    )
+    .await?;
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    node.create_subscriber_with_options(
+        "pull_subscriber_topic".to_string(),
+        Arc::new(Mutex::new(move |sample: Sample| {
+            tx.send(sample.value.payload.contiguous().to_vec()).unwrap();
+        })),
+        SubscriberOptions {
+            mode: SubscriberMode::Pull,
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    let publisher_session = create_zenoh_session().await;
+    sleep(Duration::from_millis(500)).await;
+    for i in 0..5 {
+        publisher_session
+            .put("pull_subscriber_topic", format!("sample {}", i))
+            .res()
+            .await
+            .unwrap();
+    }
+    sleep(Duration::from_millis(300)).await;
+    // Nothing is delivered until the subscriber asks for it
+    assert!(rx.try_recv().is_err());
+
+    node.pull_next("pull_subscriber_topic").await?;
+    let pulled = tokio::time::timeout(Duration::from_secs(2), rx.recv())
+        .await
+        .expect("no sample after pull")
+        .unwrap();
+    assert_eq!(pulled, b"sample 4".to_vec());
+
+    // Push-mode subscribers can't be pulled
+    node.create_subscriber(
+        "push_subscriber_topic".to_string(),
+        Arc::new(Mutex::new(|_: Sample| {})),
+    )
+    .await?;
+    assert!(matches!(
+        node.pull_next("push_subscriber_topic").await,
+        Err(FabricError::InvalidConfig(_))
+    ));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_node_config_persists_across_restarts() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let path = std::env::temp_dir().join(format!(
+        "fabric_persisted_config_{}.json",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+    let compiled_in = NodeConfig {
+        node_id: "persisted_config_node".to_string(),
+        config: serde_json::json!({ "rate": 1, "mode": "idle" }),
+    };
+    let options = NodeOptions {
+        config_path: Some(path.clone()),
+        ..Default::default()
+    };
+    let session = create_zenoh_session().await;
+
+    let node = Node::with_options(
+        "persisted_config_node".to_string(),
+        "generic".to_string(),
+        compiled_in.clone(),
+        session.clone(),
+        None,
+        options.clone(),
+    )
+    .await?;
+    node.update_config(NodeConfig {
+        node_id: "persisted_config_node".to_string(),
+        config: serde_json::json!({ "rate": 5 }),
+    })
+    .await?;
+    drop(node);
+
+    let restarted = Node::with_options(
+        "persisted_config_node".to_string(),
+        "generic".to_string(),
+        compiled_in.clone(),
+        session.clone(),
+        None,
+        options.clone(),
+    )
+    .await?;
+    assert_eq!(
+        restarted.get_config().await.config,
+        serde_json::json!({ "rate": 5, "mode": "idle" })
+    );
+
+    // A corrupt file falls back to the config passed in
+    std::fs::write(&path, b"{ not json").unwrap();
+    let recovered = Node::with_options(
+        "persisted_config_node".to_string(),
+        "generic".to_string(),
+        compiled_in.clone(),
+        session,
+        None,
+        options,
+    )
+    .await?;
+    assert_eq!(recovered.get_config().await.config, compiled_in.config);
+
+    let _ = std::fs::remove_file(&path);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_concurrent_config_saves_match_live_config() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let path = std::env::temp_dir().join(format!(
+        "fabric_concurrent_config_{}.json",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+    let compiled_in = NodeConfig {
+        node_id: "concurrent_config_node".to_string(),
+        config: serde_json::json!({ "version": 0 }),
+    };
+    let options = NodeOptions {
+        config_path: Some(path.clone()),
+        ..Default::default()
+    };
+    let session = create_zenoh_session().await;
+
+    let node = Node::with_options(
+        "concurrent_config_node".to_string(),
+        "generic".to_string(),
+        compiled_in.clone(),
+        session.clone(),
+        None,
+        options.clone(),
+    )
+    .await?;
+    let updates = (1..=20).map(|version| {
+        let node = node.clone();
+        tokio::spawn(async move {
+            let config = NodeConfig {
+                node_id: "concurrent_config_node".to_string(),
+                config: serde_json::json!({ "version": version }),
+            };
+            if version % 2 == 0 {
+                node.set_config(config).await
+            } else {
+                node.update_config(config).await
+            }
+        })
+    });
+    for result in futures::future::join_all(updates).await {
+        result.unwrap()?;
+    }
+    let live = node.get_config().await;
+    // Interface and node apply configs in the same order, so they agree on the last one
+    let interface = node.get_interface().await?;
+    assert_eq!(interface.lock().await.get_config().config, live.config);
+    drop(interface);
+    drop(node);
+
+    let restarted = Node::with_options(
+        "concurrent_config_node".to_string(),
+        "generic".to_string(),
+        compiled_in,
+        session,
+        None,
+        options,
+    )
+    .await?;
+    assert_eq!(restarted.get_config().await.config, live.config);
+
+    let _ = std::fs::remove_file(&path);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_orchestrator_snapshot_round_trip() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let path = std::env::temp_dir().join(format!(
+        "fabric_orchestrator_snapshot_{}.json",
+        std::process::id()
+    ));
+    let session = create_zenoh_session().await;
+    let orchestrator =
+        Orchestrator::new("test_snapshot_orchestrator".to_string(), session.clone()).await?;
+    {
+        let mut nodes = orchestrator.nodes.lock().await;
+        nodes.insert(
+            "snapshot_recent_node".to_string(),
+            NodeState::new(NodeData::from_fields(
+                "snapshot_recent_node".to_string(),
+                "generic".to_string(),
+                0,
+                Some(serde_json::json!({ "temp": 21 })),
+                NodeStatus::Online,
+            )),
+        );
+        let mut stale = NodeState::new(NodeData::from_fields(
+            "snapshot_stale_node".to_string(),
+            "generic".to_string(),
+            0,
+            None,
+            NodeStatus::Online,
+        ));
+        stale.last_update -= Duration::from_secs(3600);
+        nodes.insert("snapshot_stale_node".to_string(), stale);
+    }
+    orchestrator.snapshot_to(&path).await?;
+
+    let restored =
+        Orchestrator::new("test_restore_orchestrator".to_string(), session.clone()).await?;
+    restored.restore_from(&path).await?;
+    let nodes = restored.get_nodes().await;
+    assert_eq!(nodes.len(), 2);
+    let recent = &nodes["snapshot_recent_node"];
+    assert_eq!(recent.last_value.status, NodeStatus::Online);
+    assert_eq!(
+        recent.last_value.metadata,
+        Some(serde_json::json!({ "temp": 21 }))
+    );
+    let stale = &nodes["snapshot_stale_node"];
+    assert_eq!(stale.last_value.status, NodeStatus::Offline);
+    assert_eq!(
+        stale.offline_reason.as_deref(),
+        Some(OFFLINE_REASON_HEARTBEAT_TIMEOUT)
+    );
+
+    let _ = std::fs::remove_file(&path);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_concurrent_snapshots_keep_the_latest() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let path = std::env::temp_dir().join(format!(
+        "fabric_concurrent_snapshot_{}.json",
+        std::process::id()
+    ));
+    let session = create_zenoh_session().await;
+    let orchestrator = Orchestrator::new(
+        "test_concurrent_snapshot_orchestrator".to_string(),
+        session.clone(),
+    )
+    .await?;
+    let snapshots = (0..20).map(|i| {
+        let orchestrator = orchestrator.clone();
+        let path = path.clone();
+        tokio::spawn(async move {
+            let node_id = format!("concurrent_snapshot_node_{}", i);
+            orchestrator.nodes.lock().await.insert(
+                node_id.clone(),
+                NodeState::new(NodeData::from_fields(
+                    node_id,
+                    "generic".to_string(),
+                    0,
+                    None,
+                    NodeStatus::Online,
+                )),
+            );
+            orchestrator.snapshot_to(&path).await
+        })
+    });
+    for result in futures::future::join_all(snapshots).await {
+        result.unwrap()?;
+    }
+
+    let restored =
+        Orchestrator::new("test_concurrent_restore_orchestrator".to_string(), session).await?;
+    restored.restore_from(&path).await?;
+    assert_eq!(restored.get_nodes().await.len(), 20);
+
+    let _ = std::fs::remove_file(&path);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_publish_undeclared_topic_is_publisher_not_found() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let node = Node::new(
+        "undeclared_publish_node".to_string(),
+        "generic".to_string(),
+        NodeConfig {
+            node_id: "undeclared_publish_node".to_string(),
+            config: serde_json::json!({}),
+        },
+        session.clone(),
+        None,
+    )
+    .await?;
+    let orchestrator = Orchestrator::new(
+        "test_undeclared_publish_orchestrator".to_string(),
+        session.clone(),
+    )
+    .await?;
+
+    match node
+        .publish("undeclared_node_topic", b"data".to_vec())
+        .await
+    {
+        Err(FabricError::PublisherNotFound(topic)) => assert_eq!(topic, "undeclared_node_topic"),
+        other => panic!("expected PublisherNotFound, got {:?}", other),
+    }
+    match orchestrator
+        .publish("undeclared_orchestrator_topic", b"data".to_vec())
+        .await
+    {
+        Err(FabricError::PublisherNotFound(topic)) => {
+            assert_eq!(topic, "undeclared_orchestrator_topic")
+        }
+        other => panic!("expected PublisherNotFound, got {:?}", other),
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_orchestrator_stops_when_idle() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator = Orchestrator::with_config(
+        "test_idle_orchestrator".to_string(),
+        session,
+        OrchestratorConfig {
+            idle_timeout: Some(Duration::from_millis(500)),
+            ..Default::default()
+        },
+    )
+    .await?;
+    let cancel = CancellationToken::new();
+    let orchestrator_clone = orchestrator.clone();
+    let orchestrator_cancel = cancel.clone();
+    let started = std::time::Instant::now();
+    let orchestrator_handle =
+        tokio::spawn(async move { orchestrator_clone.run(orchestrator_cancel).await });
+
+    tokio::time::timeout(Duration::from_secs(3), orchestrator_handle)
+        .await
+        .expect("idle orchestrator kept running")
+        .unwrap()?;
+    assert!(started.elapsed() >= Duration::from_millis(500));
+    // Only the orchestrator stopped; the caller's token is left alone
+    assert!(!cancel.is_cancelled());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_shorter_sampling_rate_takes_effect_immediately() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let subscriber = session
+        .declare_subscriber("fabric/sampling_change_node/data")
+        .res()
+        .await?;
+    let node = Node::with_options(
+        "sampling_change_node".to_string(),
+        "generic".to_string(),
+        NodeConfig {
+            node_id: "sampling_change_node".to_string(),
+            config: serde_json::json!({ "sampling_rate": 5 }),
+        },
+        session.clone(),
+        None,
+        NodeOptions {
+            lightweight_heartbeat: true,
+            ..Default::default()
+        },
+    )
+    .await?;
+    let handle = node.clone().spawn(CancellationToken::new());
+
+    // The first tick fires straight away, the next would be 5s later
+    tokio::time::timeout(Duration::from_secs(2), subscriber.recv_async())
+        .await
+        .expect("no initial tick")
+        .unwrap();
+    node.update_config(NodeConfig {
+        node_id: "sampling_change_node".to_string(),
+        config: serde_json::json!({ "sampling_rate": 1 }),
+    })
+    .await?;
+    let changed_at = std::time::Instant::now();
+    tokio::time::timeout(Duration::from_secs(2), subscriber.recv_async())
+        .await
+        .expect("shorter sampling rate did not shorten the pending wait")
+        .unwrap();
+    assert!(changed_at.elapsed() < Duration::from_millis(1500));
+
+    handle.shutdown().await?;
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_config_publishes_respect_concurrency_limit() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator = Orchestrator::with_config(
+        "test_publish_limit_orchestrator".to_string(),
+        session.clone(),
+        OrchestratorConfig {
+            max_concurrent_publishes: Some(4),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    let configs: Vec<NodeConfig> = (0..20)
+        .map(|i| NodeConfig {
+            node_id: format!("publish_limit_node_{}", i),
+            config: serde_json::json!({ "rate": i }),
+        })
+        .collect();
+    let results = orchestrator.publish_node_configs(&configs).await?;
+    assert!(results.iter().all(|(_, result)| result.is_ok()));
+
+    let metrics = orchestrator.metrics().await;
+    assert_eq!(metrics.configs_published_total, 20);
+    assert!(
+        (1..=4).contains(&metrics.config_publishes_peak),
+        "peak concurrency was {}",
+        metrics.config_publishes_peak
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_network_topology_snapshot() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator =
+        Orchestrator::new("test_topology_orchestrator".to_string(), session.clone()).await?;
+    let snapshot = orchestrator.network_topology().await;
+    assert_eq!(snapshot.zid, session.zid().to_string());
+
+    let cancel = CancellationToken::new();
+    let orchestrator_clone = orchestrator.clone();
+    let orchestrator_cancel = cancel.clone();
+    let orchestrator_handle =
+        tokio::spawn(async move { orchestrator_clone.run(orchestrator_cancel).await });
+    sleep(Duration::from_millis(200)).await;
+
+    let replies = session
+        .get("fabric/orchestrator/test_topology_orchestrator/topology")
+        .res()
+        .await?;
+    let reply = tokio::time::timeout(Duration::from_secs(2), replies.recv_async())
+        .await
+        .expect("no topology reply")
+        .unwrap();
+    let sample = reply.sample.expect("topology query failed");
+    let served: NetworkSnapshot = serde_json::from_slice(&sample.value.payload.contiguous())?;
+    assert_eq!(served.zid, session.zid().to_string());
+
+    cancel.cancel();
+    orchestrator_handle.await.unwrap()?;
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_namespaces_isolate_fabrics() -> fabric::Result<()> {
+    init_logger(LevelFilter::Info);
+
+    // Both fabrics share one session, so only the namespace keeps them apart
+    let session = create_zenoh_session().await;
+    let cancel = CancellationToken::new();
+    let mut orchestrators = Vec::new();
+    let mut nodes = Vec::new();
+    let mut handles = Vec::new();
+    for namespace in ["site_a", "site_b"] {
+        let orchestrator = Orchestrator::with_config(
+            format!("test_{}_orchestrator", namespace),
+            session.clone(),
+            OrchestratorConfig {
+                namespace: namespace.to_string(),
+                ..Default::default()
+            },
+        )
+        .await?;
+        let orchestrator_clone = orchestrator.clone();
+        let orchestrator_cancel = cancel.clone();
+        handles.push(tokio::spawn(async move {
+            orchestrator_clone.run(orchestrator_cancel).await
+        }));
+        orchestrators.push(orchestrator);
+    }
+    sleep(Duration::from_millis(200)).await;
+
+    for namespace in ["site_a", "site_b"] {
+        let node_id = format!("{}_node", namespace);
+        let node = Node::with_options(
+            node_id.clone(),
+            "generic".to_string(),
+            NodeConfig {
+                node_id,
+                config: serde_json::json!({}),
+            },
+            session.clone(),
+            None,
+            NodeOptions {
+                namespace: namespace.to_string(),
+                ..Default::default()
+            },
+        )
+        .await?;
+        let node_clone = node.clone();
+        let node_cancel = cancel.clone();
+        handles.push(tokio::spawn(
+            async move { node_clone.run(node_cancel).await },
+        ));
+        nodes.push(node);
+    }
+    wait_for_node_initialization().await;
+
+    // Status stays in its namespace
+    for (orchestrator, namespace) in orchestrators.iter().zip(["site_a", "site_b"]) {
+        let known = orchestrator.get_nodes().await;
+        assert_eq!(known.len(), 1, "{} saw {:?}", namespace, known.keys());
+        assert!(known.contains_key(&format!("{}_node", namespace)));
+    }
+
+    // So does config: site_a's orchestrator can't reach site_b's node, but reaches its own
+    for node_id in ["site_a_node", "site_b_node"] {
+        orchestrators[0]
+            .publish_node_config(
+                node_id,
+                &NodeConfig {
+                    node_id: node_id.to_string(),
+                    config: serde_json::json!({ "from": "site_a" }),
+                },
+            )
+            .await?;
+    }
+    sleep(Duration::from_millis(300)).await;
+    assert_eq!(nodes[0].get_config().await.config["from"], "site_a");
+    assert!(nodes[1].get_config().await.config.get("from").is_none());
+
+    // site_a_node's heartbeats never land under site_b
+    let site_b_traffic = session.declare_subscriber("site_b/**").res().await?;
+    sleep(Duration::from_millis(1500)).await;
+    let keys: Vec<String> = site_b_traffic
+        .drain()
+        .map(|sample| sample.key_expr.to_string())
+        .collect();
+    assert!(keys.contains(&"site_b/site_b_node/status".to_string()));
+    assert!(keys.iter().all(|key| !key.contains("site_a")), "{:?}", keys);
+
+    cancel.cancel();
+    for handle in handles {
+        let _ = tokio::time::timeout(Duration::from_secs(5), handle).await;
+    }
+    Ok(())
+}
+
+#[cfg(feature = "testing")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_mock_node_records_applied_configs() -> fabric::Result<()> {
+    use fabric::node::interface::NodeFactory;
+    use fabric::testing::MockNodeFactory;
+
+    init_logger(LevelFilter::Info);
+
+    let session = create_zenoh_session().await;
+    let orchestrator =
+        Orchestrator::new("test_mock_orchestrator".to_string(), session.clone()).await?;
+    let factory = MockNodeFactory::new().with_reading(serde_json::json!({ "level": 7 }));
+    let config = NodeConfig {
+        node_id: "mock_node".to_string(),
+        config: serde_json::json!({ "sampling_rate": 1 }),
+    };
+    let node = Node::new(
+        "mock_node".to_string(),
+        "mock".to_string(),
+        config.clone(),
+        session.clone(),
+        Some(factory.create(config)),
+    )
+    .await?;
+    let mock = factory
+        .get("mock_node")
+        .expect("factory should keep the mock");
+
+    let cancel = CancellationToken::new();
+    let orchestrator_clone = orchestrator.clone();
+    let orchestrator_cancel = cancel.clone();
+    let orchestrator_handle =
+        tokio::spawn(async move { orchestrator_clone.run(orchestrator_cancel).await });
+    let node_cancel = cancel.clone();
+    let node_handle = tokio::spawn(async move { node.run(node_cancel).await });
+    wait_for_node_initialization().await;
+
+    orchestrator
+        .update_node_config("mock_node", serde_json::json!({ "gain": 2 }))
+        .await?;
+    sleep(Duration::from_millis(500)).await;
+
+    let applied = mock.applied_configs();
+    assert_eq!(
+        applied.last().map(|config| &config.config),
+        Some(&serde_json::json!({ "sampling_rate": 1, "gain": 2 }))
+    );
+    // Ticks publish the configured reading
+    let reading = mock.clone().tick(std::time::SystemTime::now()).await?;
+    assert_eq!(
+        reading.and_then(|node_data| node_data.metadata),
+        Some(serde_json::json!({ "level": 7 }))
+    );
+
+    cancel.cancel();
+    let _ = tokio::time::timeout(Duration::from_secs(5), node_handle).await;
+    let _ = tokio::time::timeout(Duration::from_secs(5), orchestrator_handle).await;
+    Ok(())
+}
@@ -0,0 +1,57 @@
+// Lives in its own test binary so the counting allocator only sees this test
+use fabric::payload::decode_json;
+use serde::de::IgnoredAny;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use zenoh::buffers::{ZBuf, ZSlice};
+use zenoh::prelude::r#async::*;
+
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn allocated_by<F: FnOnce()>(f: F) -> usize {
+    let before = ALLOCATED.load(Ordering::SeqCst);
+    f();
+    ALLOCATED.load(Ordering::SeqCst) - before
+}
+
+#[test]
+fn test_fragmented_payload_decode_avoids_contiguous_copy() {
+    let json = serde_json::to_vec(&(0..200_000).collect::<Vec<u32>>()).unwrap();
+    let mut payload = ZBuf::empty();
+    for chunk in json.chunks(64 * 1024) {
+        payload.push_zslice(ZSlice::from(chunk.to_vec()));
+    }
+    assert!(payload.slices().count() > 1);
+
+    let contiguous = allocated_by(|| {
+        serde_json::from_slice::<IgnoredAny>(&payload.contiguous()).unwrap();
+    });
+    let streamed = allocated_by(|| {
+        decode_json::<IgnoredAny>(&payload).unwrap();
+    });
+
+    println!(
+        "payload {} bytes: contiguous path allocated {} bytes, reader path {} bytes",
+        json.len(),
+        contiguous,
+        streamed
+    );
+    assert!(contiguous >= json.len());
+    assert!(streamed < json.len() / 10);
+}